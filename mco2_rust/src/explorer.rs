@@ -0,0 +1,350 @@
+//! Interactive report explorer (`--tui` or the menu): each report in a scrollable table that
+//! can be sorted by any column and filtered by contractor or region. Works on the report
+//! vectors already in memory; the console and file output are unchanged.
+
+use std::cmp::Ordering;
+use std::io;
+use mco2_rust::ReportTable;
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEvent, MouseEventKind,
+};
+use ratatui::crossterm::execute;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Widest a column is drawn; longer cells (contractor names) are cut off.
+const MAX_COLUMN_WIDTH: usize = 40;
+/// Columns the filter text is matched against; a report with neither matches on every column.
+const FILTER_COLUMNS: [&str; 2] = ["Contractor", "Region"];
+/// Rows moved per mouse-wheel step.
+const SCROLL_STEP: usize = 3;
+const HELP: &str = "Tab/Shift-Tab: report | Up/Down/PgUp/PgDn: scroll | Left/Right: column | \
+s or click heading: sort | /: filter | q: quit";
+
+/// Runs the explorer until the user quits, restoring the terminal afterwards.
+pub fn explore(tables: Vec<ReportTable>) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = execute!(io::stdout(), EnableMouseCapture).and_then(|()| Explorer::new(tables).run(&mut terminal));
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+    result
+}
+
+struct Explorer {
+    tables: Vec<ReportTable>,
+    current: usize,
+    /// Sorted column and whether descending; None keeps the report's own order
+    sort: Option<(usize, bool)>,
+    /// Column the cursor is on, sorted by `s`
+    column: usize,
+    /// Leftmost column drawn, so wide reports scroll sideways
+    first_column: usize,
+    filter: String,
+    editing_filter: bool,
+    /// Indices into the current table's rows that pass the filter, in display order
+    visible: Vec<usize>,
+    state: TableState,
+    /// Screen row of the headings and each drawn heading's (x start, x end, column), for
+    /// sorting by mouse click
+    heading_row: u16,
+    heading_spans: Vec<(u16, u16, usize)>,
+    /// Table rows that fit on screen, for PgUp/PgDn
+    page_rows: usize,
+}
+
+impl Explorer {
+    fn new(tables: Vec<ReportTable>) -> Self {
+        let mut explorer = Explorer {
+            tables,
+            current: 0,
+            sort: None,
+            column: 0,
+            first_column: 0,
+            filter: String::new(),
+            editing_filter: false,
+            visible: Vec::new(),
+            state: TableState::default(),
+            heading_row: 0,
+            heading_spans: Vec::new(),
+            page_rows: 1,
+        };
+        explorer.refresh();
+        explorer
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            match event::read()? {
+                Event::Key(key) if !self.handle_key(key) => return Ok(()),
+                Event::Mouse(mouse) => self.handle_mouse(mouse),
+                _ => {}
+            }
+        }
+    }
+
+    /// Recomputes the visible rows after the filter, sort or report changes.
+    fn refresh(&mut self) {
+        let Some(table) = self.tables.get(self.current) else {
+            self.visible.clear();
+            self.state.select(None);
+            return;
+        };
+        let needle = self.filter.to_lowercase();
+        let filter_columns: Vec<usize> = table
+            .headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| FILTER_COLUMNS.contains(&header.as_str()))
+            .map(|(i, _)| i)
+            .collect();
+        self.visible = (0..table.rows.len())
+            .filter(|&i| {
+                let row = &table.rows[i];
+                let matches = |cell: &String| cell.to_lowercase().contains(&needle);
+                needle.is_empty()
+                    || if filter_columns.is_empty() {
+                        row.iter().any(matches)
+                    } else {
+                        filter_columns.iter().any(|&c| matches(&row[c]))
+                    }
+            })
+            .collect();
+        if let Some((column, descending)) = self.sort {
+            // Stable, so ties keep the report's own order; blank cells always go last
+            self.visible.sort_by(|&a, &b| {
+                let (a, b) = (&table.rows[a][column], &table.rows[b][column]);
+                match (a.is_empty(), b.is_empty()) {
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    _ if descending => compare_cells(b, a),
+                    _ => compare_cells(a, b),
+                }
+            });
+        }
+        self.state.select((!self.visible.is_empty()).then_some(0));
+        *self.state.offset_mut() = 0;
+    }
+
+    fn switch_table(&mut self, forward: bool) {
+        let count = self.tables.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward { (self.current + 1) % count } else { (self.current + count - 1) % count };
+        self.sort = None;
+        self.column = 0;
+        self.first_column = 0;
+        self.refresh();
+    }
+
+    /// Sorts by `column`, flipping the direction when it is already the sort column. Numbers
+    /// start largest first and text A to Z.
+    fn sort_by(&mut self, column: usize) {
+        self.sort = match self.sort {
+            Some((sorted, descending)) if sorted == column => Some((column, !descending)),
+            _ => Some((column, self.is_numeric(column))),
+        };
+        self.refresh();
+    }
+
+    fn is_numeric(&self, column: usize) -> bool {
+        self.tables[self.current]
+            .rows
+            .iter()
+            .map(|row| &row[column])
+            .find(|cell| !cell.is_empty())
+            .is_some_and(|cell| cell.parse::<f64>().is_ok())
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let last = self.visible.len() as isize - 1;
+        self.state.select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    /// Returns false when the user quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind != KeyEventKind::Press {
+            return true;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                KeyCode::Enter => self.editing_filter = false,
+                _ => return true,
+            }
+            self.refresh();
+            return true;
+        }
+        let page = self.page_rows as isize;
+        let column_count = self.tables.get(self.current).map_or(0, |t| t.headers.len());
+        match key.code {
+            KeyCode::Char('q') => return false,
+            // Esc clears an active filter first, then quits
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.refresh();
+            }
+            KeyCode::Esc => return false,
+            KeyCode::Tab => self.switch_table(true),
+            KeyCode::BackTab => self.switch_table(false),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN / 2),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX / 2),
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.column = (self.column + 1).min(column_count.saturating_sub(1)),
+            KeyCode::Char('s') | KeyCode::Enter if column_count > 0 => self.sort_by(self.column),
+            KeyCode::Char('/') => self.editing_filter = true,
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if mouse.row == self.heading_row => {
+                let clicked = self
+                    .heading_spans
+                    .iter()
+                    .find(|(start, end, _)| (*start..*end).contains(&mouse.column))
+                    .map(|(_, _, column)| *column);
+                if let Some(column) = clicked {
+                    self.column = column;
+                    self.sort_by(column);
+                }
+            }
+            MouseEventKind::ScrollDown => self.move_selection(SCROLL_STEP as isize),
+            MouseEventKind::ScrollUp => self.move_selection(-(SCROLL_STEP as isize)),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, status_area] = Layout::vertical([Constraint::Min(4), Constraint::Length(2)]).areas(frame.area());
+        self.page_rows = table_area.height.saturating_sub(3).max(1) as usize;
+        self.heading_spans.clear();
+        let Some(table) = self.tables.get(self.current) else {
+            frame.render_widget(Paragraph::new("No report rows to show. Press q to quit."), table_area);
+            return;
+        };
+
+        let widths = column_widths(table);
+        // Scroll sideways so the cursor column is on screen
+        let inner_width = table_area.width.saturating_sub(2) as usize;
+        self.first_column = self.first_column.min(self.column);
+        while self.first_column < self.column && span_width(&widths[self.first_column..=self.column]) > inner_width {
+            self.first_column += 1;
+        }
+        let mut shown = Vec::new();
+        let mut used = 0;
+        for (column, &width) in widths.iter().enumerate().skip(self.first_column) {
+            if !shown.is_empty() && used + width > inner_width {
+                break;
+            }
+            shown.push(column);
+            used += width + 1;
+        }
+
+        self.heading_row = table_area.y + 1;
+        let mut x = table_area.x + 1;
+        let headings: Vec<Cell> = shown
+            .iter()
+            .map(|&column| {
+                self.heading_spans.push((x, x + widths[column] as u16, column));
+                x += widths[column] as u16 + 1;
+                let arrow = match self.sort {
+                    Some((sorted, true)) if sorted == column => " v",
+                    Some((sorted, false)) if sorted == column => " ^",
+                    _ => "",
+                };
+                let style = if column == self.column {
+                    Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                } else {
+                    Style::new().add_modifier(Modifier::BOLD)
+                };
+                Cell::from(format!("{}{}", table.headers[column], arrow)).style(style)
+            })
+            .collect();
+        let rows = self.visible.iter().map(|&i| {
+            Row::new(shown.iter().map(|&column| {
+                let text = table.rows[i][column].as_str();
+                if text.parse::<f64>().is_ok() {
+                    Cell::from(Line::from(text).right_aligned())
+                } else {
+                    Cell::from(text)
+                }
+            }))
+        });
+        let title = format!(
+            " {} ({} of {} rows) [{}/{}] ",
+            table.title,
+            self.visible.len(),
+            table.rows.len(),
+            self.current + 1,
+            self.tables.len()
+        );
+        let view = Table::new(rows, shown.iter().map(|&column| Constraint::Length(widths[column] as u16)))
+            .header(Row::new(headings))
+            .block(Block::bordered().title(title))
+            .column_spacing(1)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(view, table_area, &mut self.state);
+
+        let filter_line = if self.editing_filter {
+            format!("Filter by contractor/region: {}_   (Enter to keep, Esc to clear)", self.filter)
+        } else if self.filter.is_empty() {
+            "No filter; press / to filter by contractor or region.".to_string()
+        } else {
+            format!("Filter: \"{}\"   (/ to edit, Esc to clear)", self.filter)
+        };
+        frame.render_widget(Paragraph::new(vec![Line::from(filter_line), Line::from(HELP)]), status_area);
+    }
+}
+
+/// Numbers compare by value, anything else as case-insensitive text.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// Each column as wide as its heading (plus room for the sort arrow) or widest cell, up to
+/// `MAX_COLUMN_WIDTH`.
+fn column_widths(table: &ReportTable) -> Vec<usize> {
+    table
+        .headers
+        .iter()
+        .enumerate()
+        .map(|(column, header)| {
+            let widest_cell = table.rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0);
+            widest_cell.max(header.chars().count() + 2).min(MAX_COLUMN_WIDTH)
+        })
+        .collect()
+}
+
+/// Screen width of adjacent columns with their one-space gaps.
+fn span_width(widths: &[usize]) -> usize {
+    widths.iter().sum::<usize>() + widths.len().saturating_sub(1)
+}
@@ -0,0 +1,5377 @@
+/********************
+Last names: Iwata, Cunanan, Cruz, Ngandu
+Language: Rust
+Paradigm(s): Imperative, Functional, Object-Oriented
+********************/
+
+//! DPWH flood control analysis pipeline: loading, filtering and the report calculations behind
+//! the `mco2_rust` menu, callable without it.
+//!
+//! ```no_run
+//! let config = mco2_rust::Config::default();
+//! let (_, projects) = mco2_rust::load_data(&config.dataset_paths(), &config)?;
+//! let report2 = mco2_rust::build_report2(&projects, &config);
+//! let summary = mco2_rust::generate_summary(&projects, &config);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use chrono::NaiveDate;
+use csv::{StringRecord, WriterBuilder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::{Ordering, Reverse};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use sha2::{Digest, Sha256};
+
+/// Set by the Ctrl-C handler while an interruptible step (load or report writing) runs.
+pub static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// Whether an interruptible step is running; outside of one, Ctrl-C exits immediately.
+pub static BUSY: AtomicBool = AtomicBool::new(false);
+
+/// Marks a load or report write as interruptible for as long as the guard lives.
+pub struct InterruptibleStep;
+
+impl InterruptibleStep {
+    pub fn begin() -> Self {
+        INTERRUPTED.store(false, atomic::Ordering::SeqCst);
+        BUSY.store(true, atomic::Ordering::SeqCst);
+        InterruptibleStep
+    }
+}
+
+impl Drop for InterruptibleStep {
+    fn drop(&mut self) {
+        BUSY.store(false, atomic::Ordering::SeqCst);
+    }
+}
+
+pub fn was_interrupted() -> bool {
+    INTERRUPTED.load(atomic::Ordering::SeqCst)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Project {
+    /// ProjectId from the source, or "row-N" when the column is missing or blank
+    pub project_id: String,
+    pub region: String,
+    /// Province from the source, or empty when the file has no Province column
+    pub province: String,
+    pub main_island: String,
+    pub contractor: String,
+    pub funding_year: i32,
+    pub type_of_work: String,
+    #[serde(serialize_with = "ser_money")]
+    pub approved_budget: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub contract_cost: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub cost_savings: f64,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub completion_delay_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InfrastructureTrends {
+    pub region: String,
+    pub main_island: String,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget: f64,
+    /// total_budget / population, for regions listed in the --population file
+    #[serde(serialize_with = "ser_money_opt")]
+    pub budget_per_capita: Option<f64>,
+    #[serde(serialize_with = "ser_money")]
+    pub median_savings: f64,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_money_opt")]
+    pub weighted_median_savings: Option<f64>,
+    /// None when no project in the group has delay data, which is not the same as 0 days
+    #[serde(serialize_with = "ser_days_opt")]
+    pub avg_delay: Option<f64>,
+    #[serde(serialize_with = "ser_percent")]
+    pub high_delay_pct: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub overrun_rate: f64,
+    #[serde(serialize_with = "ser_score")]
+    pub efficiency_score: f64,
+    /// Efficiency score before clamping to 0-100, filled by --raw-scores
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_score_opt")]
+    pub efficiency_raw: Option<f64>,
+    /// Fewer projects than `--min-region-sample`, so the figures are unreliable
+    pub low_sample: bool,
+}
+
+/// The console's single "Other" row standing in for the Report 1 regions below
+/// `--merge-regions-below`. The CSV export still lists them individually.
+#[derive(Debug)]
+pub struct MergedRegions {
+    /// Figures recomputed over all the merged regions' projects together
+    pub row: InfrastructureTrends,
+    /// (region, main island) keys of the rows the "Other" row replaces
+    pub merged: HashSet<(String, String)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FinancialEfficiencies {
+    pub rank: i32,
+    pub contractor: String,
+    #[serde(serialize_with = "ser_money")]
+    pub total_cost: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub market_share_pct: f64,
+    pub num_projects: i32,
+    /// total_cost / num_projects
+    #[serde(serialize_with = "ser_money")]
+    pub avg_project_cost: f64,
+    /// None when no project in the group has delay data, which is not the same as 0 days
+    #[serde(serialize_with = "ser_days_opt")]
+    pub avg_delay: Option<f64>,
+    #[serde(serialize_with = "ser_money")]
+    pub total_savings: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub savings_rate_pct: f64,
+    /// Total savings per day late; equals total savings when no project ran late
+    #[serde(serialize_with = "ser_money")]
+    pub savings_per_delay_day: f64,
+    #[serde(serialize_with = "ser_score")]
+    pub reliability_index: f64,
+    /// Reliability index before capping at 100, filled by --raw-scores
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_score_opt")]
+    pub reliability_raw: Option<f64>,
+    /// Reliability index with recent funding years weighted up; see `recency_weighted_reliability`
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_score_opt")]
+    pub recent_reliability_index: Option<f64>,
+    pub risk_flag: String,
+    /// High Savings, Low Savings or Normal, from savings as a share of the approved budget;
+    /// see `savings_anomaly_flag`
+    pub savings_anomaly_flag: String,
+    pub trend: String,
+    /// Days above (+) or below (-) the dataset-wide average delay
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_days_opt")]
+    pub avg_delay_vs_dataset: Option<f64>,
+    /// Percentage points above (+) or below (-) the dataset-wide savings rate
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "ser_percent_opt")]
+    pub savings_rate_vs_dataset: Option<f64>,
+}
+
+/// How one contractor's Report 2 standing changed against a previous run's ranking file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RankingChange {
+    pub contractor: String,
+    /// New, Dropped, Up, Down or Unchanged
+    pub status: String,
+    pub previous_rank: Option<i32>,
+    /// Rank in this run, also filled for dropped contractors still ranked below the top 15
+    pub current_rank: Option<i32>,
+    /// Places moved up (+) or down (-)
+    pub rank_delta: Option<i32>,
+    #[serde(serialize_with = "ser_money_opt")]
+    pub total_cost_delta: Option<f64>,
+    #[serde(serialize_with = "ser_percent_opt")]
+    pub savings_rate_delta: Option<f64>,
+    #[serde(serialize_with = "ser_days_opt")]
+    pub avg_delay_delta: Option<f64>,
+    #[serde(serialize_with = "ser_score_opt")]
+    pub reliability_delta: Option<f64>,
+}
+
+/// One row of the --population file.
+#[derive(Debug, Deserialize)]
+struct RegionPopulation {
+    region: String,
+    population: f64,
+}
+
+/// The columns read back from a previous run's report2_contractor_ranking file.
+#[derive(Debug, Deserialize)]
+struct PreviousRanking {
+    rank: i32,
+    contractor: String,
+    total_cost: f64,
+    avg_delay: Option<f64>,
+    savings_rate_pct: f64,
+    reliability_index: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub funding_year: i32,
+    pub type_of_work: String,
+    pub total_projects: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_savings: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub avg_savings: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub overrun_rate: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub avg_utilization_pct: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub yoy_change: f64,
+    /// "adequate", "limited" or "sparse", from `total_projects`; see `sample_adequacy`
+    pub sample_adequacy: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NearMissContractor {
+    pub contractor: String,
+    pub num_projects: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractorReach {
+    pub contractor: String,
+    pub region_count: usize,
+    pub total_projects: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_cost: f64,
+}
+
+/// A contractor active in only one end of the funding-year range.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContractorChurn {
+    pub contractor: String,
+    /// "exit" (active in the first loaded year but not the last) or "entrant" (the reverse)
+    pub status: String,
+    /// Funding years with projects, e.g. "2021;2022"
+    pub active_years: String,
+    pub total_projects: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_cost: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetTierAnalysis {
+    pub budget_tier: String,
+    pub project_count: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub avg_savings_rate: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub overrun_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegionBudgetYoy {
+    pub region: String,
+    pub funding_year: i32,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget: f64,
+    #[serde(serialize_with = "ser_percent")]
+    pub yoy_change: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoundNumberFlag {
+    pub project_id: String,
+    pub contractor: String,
+    pub region: String,
+    pub funding_year: i32,
+    pub type_of_work: String,
+    #[serde(serialize_with = "ser_money")]
+    pub approved_budget: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub contract_cost: f64,
+    pub round_fields: String,
+}
+
+/// A contractor whose winning bids land exactly on the approved budget unusually often.
+#[derive(Debug, Serialize)]
+pub struct AtBudgetContractor {
+    pub contractor: String,
+    pub num_projects: usize,
+    /// Projects whose savings are within `AT_BUDGET_TOLERANCE` of zero
+    pub at_budget_projects: usize,
+    #[serde(serialize_with = "ser_percent")]
+    pub at_budget_pct: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub total_cost: f64,
+    /// The at-budget projects' ids, separated by semicolons
+    pub at_budget_project_ids: String,
+}
+
+/// Projects of one contractor in the same region and type of work with near-identical
+/// budgets, which may be a phased or re-awarded contract.
+#[derive(Debug, Serialize)]
+pub struct RepeatContractCluster {
+    pub contractor: String,
+    pub region: String,
+    pub type_of_work: String,
+    pub num_projects: usize,
+    #[serde(serialize_with = "ser_money")]
+    pub min_budget: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub max_budget: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget: f64,
+    /// Distinct funding years, separated by semicolons
+    pub funding_years: String,
+    /// The clustered projects' ids, separated by semicolons
+    pub project_ids: String,
+}
+
+/// Two distinct contractor names similar enough to possibly be the same company.
+#[derive(Debug, Serialize)]
+pub struct ContractorDuplicate {
+    pub contractor_a: String,
+    pub contractor_b: String,
+    #[serde(serialize_with = "ser_score")]
+    pub similarity: f64,
+    pub projects_a: usize,
+    pub projects_b: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OngoingProject {
+    pub project_id: String,
+    pub contractor: String,
+    pub region: String,
+    pub funding_year: i32,
+    pub type_of_work: String,
+    #[serde(serialize_with = "ser_money")]
+    pub approved_budget: f64,
+    pub start_date: NaiveDate,
+    pub days_elapsed: i64,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractorKey {
+    pub pseudonym: String,
+    pub contractor: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryJson {
+    pub total_projects_analyzed: usize,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget_analyzed: f64,
+    /// None when no project has delay data
+    #[serde(serialize_with = "ser_days_opt")]
+    pub global_avg_delay: Option<f64>,
+    /// Report 1 efficiency scores averaged with each region weighted by its total budget,
+    /// so big-budget regions count for more; not a simple mean of the regional scores
+    #[serde(serialize_with = "ser_score")]
+    pub national_efficiency_score: f64,
+    pub total_contractors: usize,
+    pub total_provinces: usize,
+    /// Total approved budget per funding year
+    #[serde(serialize_with = "ser_money_map")]
+    pub budget_by_year: BTreeMap<i32, f64>,
+    /// Sum of cost above budget over the projects counted as overruns
+    #[serde(serialize_with = "ser_money")]
+    pub total_overrun_amount: f64,
+    pub overrun_project_count: usize,
+    /// Herfindahl-Hirschman Index over every contractor's share of total contract cost,
+    /// from 0 (fragmented) to 10,000 (one contractor)
+    #[serde(serialize_with = "ser_score")]
+    pub contractor_hhi: f64,
+    /// Gini coefficient of approved budgets across projects, from 0 (all equal) towards 1
+    #[serde(serialize_with = "ser_ratio")]
+    pub budget_gini: f64,
+    /// Delay cap applied to the Report 1 and 2 averages by --cap-delays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_cap_days: Option<i64>,
+    /// Budget, savings and delay per main island; islands without projects are omitted
+    pub island_breakdown: BTreeMap<String, IslandSummary>,
+    /// SHA-256 of the input file bytes (all files in order when several were loaded)
+    pub dataset_sha256: String,
+}
+
+/// Per-island figures nested in `summary.json`.
+#[derive(Debug, Serialize)]
+pub struct IslandSummary {
+    pub project_count: usize,
+    #[serde(serialize_with = "ser_money")]
+    pub total_budget: f64,
+    #[serde(serialize_with = "ser_money")]
+    pub total_savings: f64,
+    /// None when no project in the group has delay data, which is not the same as 0 days
+    #[serde(serialize_with = "ser_days_opt")]
+    pub avg_delay: Option<f64>,
+}
+
+// Output precision: each kind of column has a default number of decimal places, and
+// --precision overrides them all. Applied when printing and when serializing to CSV/JSON.
+pub const MONEY_DECIMALS: usize = 2;
+pub const PERCENT_DECIMALS: usize = 2;
+pub const DAYS_DECIMALS: usize = 1;
+pub const SCORE_DECIMALS: usize = 2;
+pub const RATIO_DECIMALS: usize = 4;
+
+/// Decimal places set by --precision; `usize::MAX` keeps the per-column defaults.
+pub static OUTPUT_PRECISION: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+pub fn decimals(column_default: usize) -> usize {
+    match OUTPUT_PRECISION.load(atomic::Ordering::SeqCst) {
+        usize::MAX => column_default,
+        places => places,
+    }
+}
+
+pub fn round_to(v: f64, places: usize) -> f64 {
+    let factor = 10f64.powi(places.min(15) as i32);
+    let rounded = (v * factor).round() / factor;
+    // Very large values can overflow when scaled; keep them as they are
+    if rounded.is_finite() { rounded } else { v }
+}
+
+fn ser_money<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(round_to(*v, decimals(MONEY_DECIMALS)))
+}
+
+fn ser_percent<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(round_to(*v, decimals(PERCENT_DECIMALS)))
+}
+
+fn ser_days<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(round_to(*v, decimals(DAYS_DECIMALS)))
+}
+
+fn ser_score<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(round_to(*v, decimals(SCORE_DECIMALS)))
+}
+
+fn ser_ratio<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(round_to(*v, decimals(RATIO_DECIMALS)))
+}
+
+fn ser_money_opt<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => ser_money(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+fn ser_days_opt<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => ser_days(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+fn ser_percent_opt<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => ser_percent(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+fn ser_score_opt<S: Serializer>(v: &Option<f64>, s: S) -> Result<S::Ok, S::Error> {
+    match v {
+        Some(v) => ser_score(v, s),
+        None => s.serialize_none(),
+    }
+}
+
+fn ser_money_map<S: Serializer>(map: &BTreeMap<i32, f64>, s: S) -> Result<S::Ok, S::Error> {
+    let places = decimals(MONEY_DECIMALS);
+    s.collect_map(map.iter().map(|(k, v)| (k, round_to(*v, places))))
+}
+
+const DEFAULT_DATASET_PATH: &str = "../dpwh_flood_control_projects.csv";
+
+/// Dataset path that reads the CSV from standard input, e.g. `cat data.csv | mco2_rust - --generate`.
+pub const STDIN_PATH: &str = "-";
+
+/// Funding years kept by `parse_data` (REQ-0003).
+pub const FUNDING_YEARS: std::ops::RangeInclusive<i32> = 2021..=2023;
+
+/// Report 2 rows written to the ranking file and shown on the console.
+pub const REPORT_2_TOP_N: usize = 15;
+
+/// Every column of the standard DPWH layout, in order, with the other header spellings
+/// accepted for it. Headers are matched ignoring case and whitespace, so only spellings that
+/// differ in more than that need listing.
+const COLUMN_ALIASES: [(&str, &[&str]); 22] = [
+    ("MainIsland", &["Island"]),
+    ("Region", &[]),
+    ("Province", &[]),
+    ("LegislativeDistrict", &["District"]),
+    ("Municipality", &["City/Municipality", "Municipality/City"]),
+    ("DistrictEngineeringOffice", &["DEO", "Implementing Office"]),
+    ("ProjectId", &["Project Code"]),
+    ("ProjectName", &["Project Description", "Project Title"]),
+    ("TypeOfWork", &["Work Type", "Category"]),
+    ("FundingYear", &["FY", "Fiscal Year", "Year"]),
+    ("ContractId", &["Contract No", "Contract No.", "Contract Number"]),
+    ("ApprovedBudgetForContract", &["Approved Budget for the Contract", "ABC", "Approved Budget"]),
+    ("ContractCost", &["Contract Amount", "Contract Price"]),
+    ("ActualCompletionDate", &["Completion Date", "Date Completed"]),
+    ("Contractor", &["Contractor Name", "Winning Contractor"]),
+    ("ContractorCount", &["Number of Contractors"]),
+    ("StartDate", &["Date Started", "Commencement Date"]),
+    ("ProjectLatitude", &["Latitude"]),
+    ("ProjectLongitude", &["Longitude"]),
+    ("ProvincialCapital", &[]),
+    ("ProvincialCapitalLatitude", &[]),
+    ("ProvincialCapitalLongitude", &[]),
+];
+
+/// Where each standard column sits in a file's header, found through `COLUMN_ALIASES`, so
+/// files with renamed or reordered headers are read into the standard layout.
+///
+/// ```
+/// use mco2_rust::ColumnMapping;
+///
+/// for spelling in ["ApprovedBudgetForContract", "Approved Budget for the Contract", "ABC", " abc "] {
+///     let mapping = ColumnMapping::new(["Region", "Funding Year", spelling]);
+///     assert_eq!(mapping.column("ApprovedBudgetForContract"), Some(2));
+///     assert_eq!(mapping.column("FundingYear"), Some(1));
+/// }
+/// let mapping = ColumnMapping::new(["Fiscal Year", "ABC"]);
+/// assert_eq!(mapping.aliases_used(), [("FundingYear", "Fiscal Year".to_string()), ("ApprovedBudgetForContract", "ABC".to_string())]);
+/// assert_eq!(mapping.column("Region"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    /// Source index of each standard column, by its position in `COLUMN_ALIASES`
+    source: Vec<Option<usize>>,
+    /// (standard name, header as written) for columns found under another spelling
+    aliases_used: Vec<(&'static str, String)>,
+}
+
+impl ColumnMapping {
+    pub fn new<'a>(headers: impl IntoIterator<Item = &'a str>) -> Self {
+        let normalize = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+        let headers: Vec<(&str, String)> = headers.into_iter().map(|h| (h, normalize(h))).collect();
+        let mut source = Vec::new();
+        let mut aliases_used = Vec::new();
+        for (name, aliases) in COLUMN_ALIASES {
+            let spellings: Vec<String> = std::iter::once(name).chain(aliases.iter().copied()).map(normalize).collect();
+            let found = headers.iter().position(|(_, header)| spellings.contains(header));
+            if let Some(index) = found
+                && headers[index].1 != normalize(name)
+            {
+                aliases_used.push((name, headers[index].0.trim().to_string()));
+            }
+            source.push(found);
+        }
+        ColumnMapping { source, aliases_used }
+    }
+
+    /// Source index of a standard column, e.g. `column("ContractCost")`.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        let position = COLUMN_ALIASES.iter().position(|(standard, _)| *standard == name)?;
+        self.source[position]
+    }
+
+    pub fn aliases_used(&self) -> &[(&'static str, String)] {
+        &self.aliases_used
+    }
+
+    /// Standard columns the file has no header for.
+    pub fn missing(&self) -> Vec<&'static str> {
+        COLUMN_ALIASES
+            .iter()
+            .zip(&self.source)
+            .filter(|(_, source)| source.is_none())
+            .map(|((name, _), _)| *name)
+            .collect()
+    }
+
+    /// True when every standard column is already in its standard position.
+    fn is_standard(&self) -> bool {
+        self.source.iter().enumerate().all(|(i, source)| *source == Some(i))
+    }
+
+    /// Whether the file has a header for the column at `index` of a standardized record.
+    /// Columns past the standard layout come straight from the file, so they always do.
+    fn has_source_column(&self, index: usize) -> bool {
+        self.source.get(index).is_none_or(Option::is_some)
+    }
+
+    /// The record rearranged into the standard layout; a missing column reads as blank.
+    fn standardize(&self, record: &StringRecord) -> StringRecord {
+        self.source
+            .iter()
+            .map(|source| source.and_then(|i| record.get(i)).unwrap_or(""))
+            .collect()
+    }
+}
+
+/// Source columns read by `parse_data`, as (index, expected header name).
+const REQUIRED_COLUMNS: [(usize, &str); 9] = [
+    (0, "MainIsland"),
+    (1, "Region"),
+    (8, "TypeOfWork"),
+    (9, "FundingYear"),
+    (11, "ApprovedBudgetForContract"),
+    (12, "ContractCost"),
+    (13, "ActualCompletionDate"),
+    (14, "Contractor"),
+    (16, "StartDate"),
+];
+
+/// Columns a row cannot be analyzed without. With `--keep-partial-rows` only a blank in one of
+/// these drops the row; every other column may be blank.
+const CRITICAL_COLUMNS: [(usize, &str); 3] = [
+    (9, "FundingYear"),
+    (11, "ApprovedBudgetForContract"),
+    (12, "ContractCost"),
+];
+
+/// Funding years that could be real. A year outside this range almost certainly comes from the
+/// wrong column, e.g. after a column was inserted upstream.
+pub const PLAUSIBLE_FUNDING_YEARS: std::ops::RangeInclusive<i32> = 1990..=2100;
+
+/// Stands in for a blank text field on rows kept by `--keep-partial-rows`.
+const BLANK_FIELD_PLACEHOLDER: &str = "Unspecified";
+
+/// Optional source column; when a file lacks it, provinces are approximated by regions.
+const PROVINCE_COLUMN: (usize, &str) = (2, "Province");
+
+/// Canonical main island for each region, used to repair inconsistent island labels.
+const REGION_ISLANDS: [(&str, &str); 17] = [
+    ("National Capital Region", "Luzon"),
+    ("Cordillera Administrative Region", "Luzon"),
+    ("Region I", "Luzon"),
+    ("Region II", "Luzon"),
+    ("Region III", "Luzon"),
+    ("Region IV-A", "Luzon"),
+    ("Region IV-B", "Luzon"),
+    ("Region V", "Luzon"),
+    ("Region VI", "Visayas"),
+    ("Region VII", "Visayas"),
+    ("Region VIII", "Visayas"),
+    ("Region IX", "Mindanao"),
+    ("Region X", "Mindanao"),
+    ("Region XI", "Mindanao"),
+    ("Region XII", "Mindanao"),
+    ("Region XIII", "Mindanao"),
+    ("Bangsamoro Autonomous Region in Muslim Mindanao", "Mindanao"),
+];
+
+/// Budget tiers for the savings-by-size breakdown, as (label, exclusive upper bound).
+/// A project falls in the first tier whose bound exceeds its approved budget.
+const BUDGET_TIERS: [(&str, f64); 4] = [
+    ("< 10M", 10_000_000.0),
+    ("10M - 100M", 100_000_000.0),
+    ("100M - 1B", 1_000_000_000.0),
+    (">= 1B", f64::INFINITY),
+];
+
+/// Number of data rows parsed by the "Validate Dataset" check.
+const VALIDATION_SAMPLE_ROWS: usize = 100;
+
+/// How projects without a computable delay enter the delay averages.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingDelayPolicy {
+    Exclude,
+    #[serde(rename = "zero")]
+    TreatAsZero,
+}
+
+impl MissingDelayPolicy {
+    pub fn label(self) -> &'static str {
+        match self {
+            MissingDelayPolicy::Exclude => "Missing delays excluded",
+            MissingDelayPolicy::TreatAsZero => "Missing delays counted as 0 days",
+        }
+    }
+}
+
+impl std::str::FromStr for MissingDelayPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exclude" => Ok(MissingDelayPolicy::Exclude),
+            "zero" => Ok(MissingDelayPolicy::TreatAsZero),
+            _ => Err(format!("Unknown missing-delay policy: {}", s)),
+        }
+    }
+}
+
+/// Sort key used to assign Report 2 ranks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankMode {
+    Cost,
+    Reliability,
+    Savings,
+}
+
+impl RankMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            RankMode::Cost => "Total Contract Cost",
+            RankMode::Reliability => "Reliability Index",
+            RankMode::Savings => "Total Savings",
+        }
+    }
+}
+
+impl std::str::FromStr for RankMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cost" => Ok(RankMode::Cost),
+            "reliability" => Ok(RankMode::Reliability),
+            "savings" => Ok(RankMode::Savings),
+            _ => Err(format!("Unknown rank mode: {}", s)),
+        }
+    }
+}
+
+/// How far a contract cost must exceed its approved budget to count as an overrun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverrunMargin {
+    Amount(f64),
+    Percent(f64),
+}
+
+impl OverrunMargin {
+    pub fn is_overrun(self, project: &Project) -> bool {
+        let excess = project.contract_cost - project.approved_budget;
+        match self {
+            OverrunMargin::Amount(amount) => excess > amount,
+            OverrunMargin::Percent(pct) => excess > project.approved_budget.abs() * pct / 100.0,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            OverrunMargin::Amount(0.0) => "Overrun: any cost above budget".to_string(),
+            OverrunMargin::Amount(amount) => format!("Overrun: cost above budget by more than {:.2}", amount),
+            OverrunMargin::Percent(pct) => format!("Overrun: cost above budget by more than {}%", pct),
+        }
+    }
+}
+
+/// Written in the form `FromStr` reads: "50000" or "2.5%".
+impl Serialize for OverrunMargin {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            OverrunMargin::Amount(amount) => s.serialize_str(&amount.to_string()),
+            OverrunMargin::Percent(pct) => s.serialize_str(&format!("{}%", pct)),
+        }
+    }
+}
+
+impl std::str::FromStr for OverrunMargin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, is_percent) = match s.strip_suffix('%') {
+            Some(number) => (number.trim(), true),
+            None => (s, false),
+        };
+        let value: f64 = number
+            .parse()
+            .map_err(|_| format!("Invalid overrun margin: {}", s))?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("Overrun margin must be a non-negative number: {}", s));
+        }
+        Ok(if is_percent { OverrunMargin::Percent(value) } else { OverrunMargin::Amount(value) })
+    }
+}
+
+/// File format(s) the report tables are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    Both,
+}
+
+impl TableFormat {
+    /// (extension, delimiter) for each file written per table.
+    fn outputs(self) -> &'static [(&'static str, u8)] {
+        match self {
+            TableFormat::Csv => &[("csv", b',')],
+            TableFormat::Tsv => &[("tsv", b'\t')],
+            TableFormat::Both => &[("csv", b','), ("tsv", b'\t')],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "CSV",
+            TableFormat::Tsv => "TSV",
+            TableFormat::Both => "CSV and TSV",
+        }
+    }
+
+    /// The file names a table named `stem` is written to, for display.
+    fn file_names(self, stem: &str) -> String {
+        self.outputs()
+            .iter()
+            .map(|(extension, _)| format!("{}.{}", stem, extension))
+            .collect::<Vec<_>>()
+            .join(" and ")
+    }
+}
+
+impl std::str::FromStr for TableFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(TableFormat::Csv),
+            "tsv" => Ok(TableFormat::Tsv),
+            "both" => Ok(TableFormat::Both),
+            _ => Err(format!("Unknown table format: {}", s)),
+        }
+    }
+}
+
+/// Per-project value screened for outliers before the trimmed rerun.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlierMetric {
+    Cost,
+    Savings,
+    Delay,
+}
+
+impl OutlierMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            OutlierMetric::Cost => "contract cost",
+            OutlierMetric::Savings => "cost savings",
+            OutlierMetric::Delay => "completion delay",
+        }
+    }
+
+    pub fn value(self, project: &Project) -> Option<f64> {
+        match self {
+            OutlierMetric::Cost => Some(project.contract_cost),
+            OutlierMetric::Savings => Some(project.cost_savings),
+            OutlierMetric::Delay => project.completion_delay_days.map(|d| d as f64),
+        }
+    }
+}
+
+impl std::str::FromStr for OutlierMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cost" => Ok(OutlierMetric::Cost),
+            "savings" => Ok(OutlierMetric::Savings),
+            "delay" => Ok(OutlierMetric::Delay),
+            _ => Err(format!("Unknown outlier metric: {}", s)),
+        }
+    }
+}
+
+/// How tied Report 2 rows are numbered.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RankStyle {
+    /// 1, 2, 3, 4: ties still get distinct ranks
+    Ordinal,
+    /// 1, 2, 2, 4: ties share a rank and the next one skips
+    Competition,
+    /// 1, 2, 2, 3: ties share a rank with no gap after
+    Dense,
+}
+
+impl std::str::FromStr for RankStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ordinal" => Ok(RankStyle::Ordinal),
+            "competition" => Ok(RankStyle::Competition),
+            "dense" => Ok(RankStyle::Dense),
+            _ => Err(format!("Unknown rank style: {}", s)),
+        }
+    }
+}
+
+/// Outputs that `--reports` can select.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportKind {
+    /// Report 1
+    Regional,
+    /// Report 2 with its near-miss list and optional project lists
+    Contractors,
+    /// Report 3
+    Annual,
+    #[serde(rename = "tiers")]
+    BudgetTiers,
+    /// Approved budget per region and year with year-over-year change
+    RegionYoy,
+    Reach,
+    /// Contractors entering or leaving between the first and last loaded funding year
+    Churn,
+    /// summary.json and the history file
+    Summary,
+}
+
+impl std::str::FromStr for ReportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "1" | "regional" => Ok(ReportKind::Regional),
+            "2" | "contractors" => Ok(ReportKind::Contractors),
+            "3" | "annual" => Ok(ReportKind::Annual),
+            "tiers" => Ok(ReportKind::BudgetTiers),
+            "region-yoy" => Ok(ReportKind::RegionYoy),
+            "reach" => Ok(ReportKind::Reach),
+            "churn" => Ok(ReportKind::Churn),
+            "summary" => Ok(ReportKind::Summary),
+            _ => Err(format!("Unknown report: {}", s)),
+        }
+    }
+}
+
+/// Run settings collected from the command line. Serialized as-is into run_config.json,
+/// minus the fields that only change what the console shows.
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    pub file_paths: Vec<String>,
+    pub row_limit: Option<usize>,
+    #[serde(skip)]
+    pub quiet: bool,
+    #[serde(skip)]
+    pub generate: bool,
+    /// Open the generated reports in the interactive explorer
+    #[serde(skip)]
+    pub tui: bool,
+    pub min_projects: usize,
+    /// Report 2 also drops contractors whose total contract cost is below this; asked for
+    /// interactively when not given, and 0 (no floor) in batch runs
+    pub min_total_cost: Option<f64>,
+    /// Report 1 rows with fewer projects than this are flagged as low-sample
+    pub min_region_sample: usize,
+    /// Report 1 regions with fewer projects than this share one "Other" row on the console
+    pub merge_regions_below: Option<usize>,
+    /// Funding years before 2021 loaded only as the previous year for YoY changes
+    pub context_years: i32,
+    /// Totals from those context years, resolved from the loaded projects when reports are built
+    #[serde(skip)]
+    pub yoy_baselines: YoyBaselines,
+    /// Report 3 cells with at least this many projects are "limited" rather than "sparse"
+    pub limited_sample_min: usize,
+    /// Report 3 cells with at least this many projects are "adequate"
+    pub adequate_sample_min: usize,
+    pub near_miss_min: usize,
+    pub near_miss_max: Option<usize>,
+    pub correct_islands: bool,
+    pub append_history: bool,
+    pub missing_delay: MissingDelayPolicy,
+    pub overrun_margin: OverrunMargin,
+    /// Percentile at which delays are capped before the Report 1 and 2 averages
+    pub delay_cap_percentile: Option<f64>,
+    /// That percentile's value in days, resolved from the projects when reports are built
+    pub delay_cap: Option<i64>,
+    pub work_types: Vec<String>,
+    pub round_divisor: f64,
+    pub weighted_median: bool,
+    /// Add the unclamped efficiency score and uncapped reliability index as extra columns
+    pub raw_scores: bool,
+    pub ongoing_threshold_days: i64,
+    /// Minimum similarity (0-1) for two contractor names to be listed as likely duplicates
+    pub dupe_threshold: f64,
+    /// Share of projects (0-1) bid exactly at budget above which a contractor is flagged
+    pub at_budget_fraction: f64,
+    /// Budget spread, as a fraction of the smallest, allowed within a repeat-contract cluster
+    pub repeat_tolerance: f64,
+    pub start_window: StartDateWindow,
+    pub min_kept_fraction: Option<f64>,
+    pub anonymize: bool,
+    pub future_date_margin_days: i64,
+    pub null_future_delays: bool,
+    /// Swap start and completion dates on rows where completion comes first
+    pub fix_swapped_dates: bool,
+    /// Drop rows only when a critical column is blank
+    pub keep_partial_rows: bool,
+    /// Abort the load on the first row that fails to parse instead of skipping it
+    pub strict: bool,
+    pub large_file: bool,
+    pub rank_by: RankMode,
+    pub rank_style: RankStyle,
+    pub precision: Option<usize>,
+    pub contractor_projects: Option<usize>,
+    pub compare_to_average: bool,
+    /// Adds a Report 2 reliability index weighting each funding year back by this factor
+    pub recency_decay: Option<f64>,
+    /// Report 2 savings (as a % of approved budget) above this are flagged "High Savings"
+    pub savings_flag_high: f64,
+    /// ... and below this "Low Savings"
+    pub savings_flag_low: f64,
+    /// Colour the Report 2 risk flags on a terminal (off with --no-color or NO_COLOR)
+    #[serde(skip)]
+    pub color: bool,
+    /// Pause the console Report 1-3 tables every this many rows until Enter is pressed
+    #[serde(skip)]
+    pub page_size: Option<usize>,
+    /// Previous run's report2_contractor_ranking file to diff the new ranking against
+    pub compare_ranking: Option<String>,
+    /// region,population CSV used for the Report 1 budget per capita
+    pub population_file: Option<String>,
+    #[serde(skip)]
+    pub show_version: bool,
+    /// Run the self-test over this many synthetic projects instead of the menu
+    #[serde(skip)]
+    pub self_test: Option<usize>,
+    #[serde(skip)]
+    pub write_sample_config: bool,
+    /// Value whose IQR fences decide which projects the trimmed rerun drops
+    pub outlier_metric: OutlierMetric,
+    /// Set for the outlier-trimmed rerun, which labels its reports and writes `trimmed_*` files
+    pub outliers_trimmed: bool,
+    /// Run the reports once per funding year instead of over the whole selection
+    pub partition_by_year: bool,
+    /// Set for each pass of the per-year run, which labels its reports and writes `*_YEAR` files
+    pub partition_year: Option<i32>,
+    /// Reports to produce; empty means all of them
+    pub reports: Vec<ReportKind>,
+    pub table_format: TableFormat,
+    /// Also write Report 1 as report1_regions.geojson
+    pub geojson: bool,
+    /// Also write Reports 1-3 as standalone report1.html, report2.html and report3.html
+    pub html: bool,
+    /// Read every written table back and check it round-trips
+    pub verify_output: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            file_paths: Vec::new(),
+            row_limit: None,
+            quiet: false,
+            generate: false,
+            tui: false,
+            min_projects: 5, // per REQ-0007
+            min_total_cost: None,
+            min_region_sample: 3,
+            merge_regions_below: None,
+            context_years: 0,
+            yoy_baselines: YoyBaselines::default(),
+            limited_sample_min: 3,
+            adequate_sample_min: 10,
+            near_miss_min: 2,
+            near_miss_max: None,
+            correct_islands: true,
+            append_history: false,
+            missing_delay: MissingDelayPolicy::Exclude,
+            overrun_margin: OverrunMargin::Amount(0.0),
+            delay_cap_percentile: None,
+            delay_cap: None,
+            work_types: Vec::new(),
+            round_divisor: 1_000_000.0,
+            weighted_median: false,
+            raw_scores: false,
+            ongoing_threshold_days: 365,
+            dupe_threshold: 0.9,
+            at_budget_fraction: 0.5,
+            repeat_tolerance: 0.02,
+            start_window: StartDateWindow::default(),
+            min_kept_fraction: None,
+            anonymize: false,
+            future_date_margin_days: 30,
+            null_future_delays: false,
+            fix_swapped_dates: false,
+            keep_partial_rows: false,
+            strict: false,
+            large_file: false,
+            rank_by: RankMode::Cost,
+            rank_style: RankStyle::Ordinal,
+            precision: None,
+            contractor_projects: None,
+            compare_to_average: false,
+            recency_decay: None,
+            savings_flag_high: 40.0,
+            savings_flag_low: 0.0,
+            compare_ranking: None,
+            population_file: None,
+            color: true,
+            page_size: None,
+            show_version: false,
+            self_test: None,
+            write_sample_config: false,
+            outlier_metric: OutlierMetric::Cost,
+            outliers_trimmed: false,
+            partition_by_year: false,
+            partition_year: None,
+            reports: Vec::new(),
+            table_format: TableFormat::Csv,
+            geojson: false,
+            html: false,
+            verify_output: false,
+        }
+    }
+}
+
+impl Config {
+    /// True when `total_cost` falls below the Report 2 total-cost floor.
+    pub fn below_cost_floor(&self, total_cost: f64) -> bool {
+        total_cost < self.min_total_cost.unwrap_or(0.0)
+    }
+
+    /// Funding years read from the dataset: the analyzed range plus any context years.
+    pub fn loaded_years(&self) -> std::ops::RangeInclusive<i32> {
+        (*FUNDING_YEARS.start() - self.context_years)..=*FUNDING_YEARS.end()
+    }
+
+    /// The context years for messages, e.g. "2020" or "2019-2020".
+    pub fn context_years_label(&self) -> String {
+        let first = *self.loaded_years().start();
+        let last = FUNDING_YEARS.start() - 1;
+        if first == last { first.to_string() } else { format!("{}-{}", first, last) }
+    }
+
+    /// Dataset files to read, falling back to the default path when none were given.
+    pub fn dataset_paths(&self) -> Vec<String> {
+        if self.file_paths.is_empty() {
+            vec![DEFAULT_DATASET_PATH.to_string()]
+        } else {
+            self.file_paths.clone()
+        }
+    }
+
+    /// Output file name, prefixed for the outlier-trimmed rerun and suffixed with the year for a
+    /// per-year pass, so the combined run's files stay intact.
+    fn output_name(&self, name: &str) -> String {
+        let name = match (self.partition_year, name.rsplit_once('.')) {
+            (Some(year), Some((stem, extension))) => format!("{}_{}.{}", stem, year, extension),
+            (Some(year), None) => format!("{}_{}", name, year),
+            (None, _) => name.to_string(),
+        };
+        if self.outliers_trimmed {
+            format!("trimmed_{}", name)
+        } else {
+            name
+        }
+    }
+
+    /// True for the outlier-trimmed rerun and the per-year passes, which write their own file
+    /// names and stay out of the summary history.
+    pub fn is_derived_run(&self) -> bool {
+        self.outliers_trimmed || self.partition_year.is_some()
+    }
+
+    pub fn wants(&self, kind: ReportKind) -> bool {
+        self.reports.is_empty() || self.reports.contains(&kind)
+    }
+
+    /// Upper bound of the near-miss band, defaulting to just below the Report 2 threshold.
+    fn near_miss_upper(&self) -> usize {
+        self.near_miss_max
+            .unwrap_or_else(|| self.min_projects.saturating_sub(1))
+    }
+}
+
+/// Keeps the first `max` characters of `s`, appending ".." when anything was cut. Counts
+/// characters rather than bytes, so names with ñ or é never split mid-character.
+pub fn truncate(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}..", &s[..cut]),
+        None => s.to_string(),
+    }
+}
+
+/// Tukey fences (Q1 - 1.5 IQR, Q3 + 1.5 IQR) for `metric`, or None with fewer than 4 values.
+pub fn iqr_fences(projects: &[Project], metric: OutlierMetric) -> Option<(f64, f64)> {
+    let mut values: Vec<f64> = projects.iter().filter_map(|p| metric.value(p)).collect();
+    if values.len() < 4 {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let half = values.len() / 2;
+    let upper_start = values.len() - half;
+    let q1 = median(&mut values[..half]);
+    let q3 = median(&mut values[upper_start..]);
+    let iqr = q3 - q1;
+    Some((q1 - 1.5 * iqr, q3 + 1.5 * iqr))
+}
+
+/// Renders the summary figures and every report table. Used for both the console and
+/// `report.txt`, so the two always match; `console` adds display-only extras such as the
+/// efficiency bars.
+pub fn write_text_report(out: &mut impl Write, reports: &Reports, config: &Config, console: bool) -> io::Result<()> {
+    let pager = Pager::new(config, console);
+    if config.wants(ReportKind::Summary) {
+        write_summary(out, &reports.summary)?;
+    }
+    if config.wants(ReportKind::Regional) {
+        write_report_1(
+            out,
+            &reports.report1,
+            reports.report1_merged.as_ref(),
+            reports.population_matched,
+            config,
+            console,
+            &pager,
+        )?;
+    }
+    if config.wants(ReportKind::Contractors) {
+        write_report_2(
+            out,
+            &reports.report2,
+            reports.report2_below_cost_floor,
+            config,
+            console && use_color(config),
+            &pager,
+        )?;
+        write_near_miss_contractors(out, &reports.near_miss, config)?;
+        if !reports.ranking_changes.is_empty() {
+            write_ranking_changes(out, &reports.ranking_changes, config)?;
+        }
+    }
+    if config.wants(ReportKind::Reach) {
+        write_contractor_reach(out, &reports.reach, config)?;
+    }
+    if config.wants(ReportKind::Annual) {
+        write_report_3(out, &reports.report3, config, &pager)?;
+    }
+    if config.wants(ReportKind::BudgetTiers) {
+        write_budget_tiers(out, &reports.budget_tiers, config)?;
+    }
+    if config.wants(ReportKind::RegionYoy) {
+        write_region_budget_yoy(out, &reports.region_yoy, config)?;
+    }
+    if config.wants(ReportKind::Churn) {
+        write_contractor_churn(out, &reports.churn, &reports.contractors_by_year, config)?;
+    }
+    Ok(())
+}
+
+/// Splits the console report tables into pages of `--page-size` rows. Paging is off unless
+/// both stdin and stdout are terminals and --quiet is not set, so piped runs never block.
+struct Pager {
+    page_size: Option<usize>,
+}
+
+impl Pager {
+    fn new(config: &Config, console: bool) -> Self {
+        let interactive = console && !config.quiet && io::stdout().is_terminal() && io::stdin().is_terminal();
+        Pager { page_size: config.page_size.filter(|_| interactive) }
+    }
+
+    /// Call before writing row `index` of `total`; waits for Enter at every page boundary.
+    fn before_row(&self, out: &mut impl Write, index: usize, total: usize) -> io::Result<()> {
+        let Some(size) = self.page_size else {
+            return Ok(());
+        };
+        if index == 0 || !index.is_multiple_of(size) {
+            return Ok(());
+        }
+        write!(out, "-- {} of {} rows shown; press Enter for more --", index, total)?;
+        out.flush()?;
+        io::stdin().read_line(&mut String::new())?;
+        Ok(())
+    }
+}
+
+fn write_summary(out: &mut impl Write, summary: &SummaryJson) -> io::Result<()> {
+    writeln!(out, "\n{:-<60}", "")?;
+    writeln!(out, "Summary")?;
+    writeln!(out, "{:-<60}", "")?;
+    writeln!(out, "{:<28} {:>22}", "Projects analyzed:", summary.total_projects_analyzed)?;
+    writeln!(out, "{:<28} {:>22}", "Total budget analyzed:", fmt_peso(summary.total_budget_analyzed))?;
+    writeln!(
+        out,
+        "{:<28} {:>22.score$}   (budget-weighted across regions)",
+        "National efficiency score:",
+        summary.national_efficiency_score,
+        score = decimals(SCORE_DECIMALS)
+    )?;
+    let days = decimals(DAYS_DECIMALS);
+    writeln!(out, "{:<28} {:>22}", "Global avg delay (days):", fmt_days(summary.global_avg_delay))?;
+    writeln!(out, "{:<28} {:>22}", "Total contractors:", summary.total_contractors)?;
+    writeln!(out, "{:<28} {:>22}", "Total provinces:", summary.total_provinces)?;
+    for (year, budget) in &summary.budget_by_year {
+        writeln!(out, "{:<28} {:>22}", format!("Budget, FY {}:", year), fmt_peso(*budget))?;
+    }
+    writeln!(out, "{:<28} {:>22}", "Overrun projects:", summary.overrun_project_count)?;
+    writeln!(out, "{:<28} {:>22}", "Total overrun amount:", fmt_peso(summary.total_overrun_amount))?;
+    writeln!(
+        out,
+        "{:<28} {:>22.score$}   ({})",
+        "Contractor HHI:",
+        summary.contractor_hhi,
+        hhi_label(summary.contractor_hhi),
+        score = decimals(SCORE_DECIMALS)
+    )?;
+    writeln!(
+        out,
+        "{:<28} {:>22.ratio$}",
+        "Budget Gini:",
+        summary.budget_gini,
+        ratio = decimals(RATIO_DECIMALS)
+    )?;
+    for (island, figures) in &summary.island_breakdown {
+        writeln!(
+            out,
+            "{:<28} {:>22}   savings {}, avg delay {}",
+            format!("Budget, {}:", island),
+            fmt_peso(figures.total_budget),
+            fmt_peso(figures.total_savings),
+            figures.avg_delay.map_or("N/A".to_string(), |d| format!("{:.days$} days", d))
+        )?;
+    }
+    writeln!(out, "{:-<60}", "")
+}
+
+fn write_report_1(
+    out: &mut impl Write,
+    report: &[InfrastructureTrends],
+    merged: Option<&MergedRegions>,
+    population_matched: Option<usize>,
+    config: &Config,
+    show_bars: bool,
+    pager: &Pager,
+) -> io::Result<()> {
+    let reports_per_capita = population_matched.is_some();
+    let pct = decimals(PERCENT_DECIMALS);
+    let score = decimals(SCORE_DECIMALS);
+    let mut width = if config.weighted_median { 166 } else { 145 };
+    if config.raw_scores {
+        width += 15;
+    }
+    if reports_per_capita {
+        width += 15;
+    }
+    if show_bars {
+        width += EFFICIENCY_BAR_WIDTH + 3;
+    }
+    let max_score = report.iter().map(|r| r.efficiency_score).fold(0.0, f64::max);
+    writeln!(out, "\n{:-<width$}", "")?;
+    writeln!(out, "Report 1: Regional Flood Mitigation Efficiency Summary")?;
+    writeln!(out, "(Filtered: 2021-2023 Projects; {})", config.missing_delay.label())?;
+    write_filter_notes(out, config)?;
+    write_delay_cap_note(out, config)?;
+    if let (Some(matched), Some(path)) = (population_matched, &config.population_file) {
+        writeln!(out, "(Per Capita: {} of {} regions matched in {})", matched, report.len(), path)?;
+    }
+    writeln!(out, "{:-<width$}", "")?;
+    let mut header = format!("{:<20} | {:<15} | {:>18}", "Region", "Main Island", "Total Budget");
+    if reports_per_capita {
+        header += &format!(" | {:>12}", "Per Capita");
+    }
+    header += &format!(" | {:>18}", "Median Savings");
+    if config.weighted_median {
+        header += &format!(" | {:>18}", "W. Median Savings");
+    }
+    header += &format!(
+        " | {:>12} | {:>12} | {:>12} | {:>12}",
+        "Avg Delay", "High Delay %", "Overrun %", "Efficiency"
+    );
+    if config.raw_scores {
+        header += &format!(" | {:>12}", "Raw Eff.");
+    }
+    if show_bars {
+        header += " | Relative Efficiency";
+    }
+    writeln!(out, "{}", header)?;
+    writeln!(out, "{:-<width$}", "")?;
+
+    let shown: Vec<&InfrastructureTrends> = report
+        .iter()
+        .filter(|r| merged.is_none_or(|m| !m.merged.contains(&(r.region.clone(), r.main_island.clone()))))
+        .chain(merged.map(|m| &m.row))
+        .collect();
+    for (i, r) in shown.iter().enumerate() {
+        pager.before_row(out, i, shown.len())?;
+        let region = if r.low_sample {
+            format!("{}*", truncate(&r.region, 17))
+        } else {
+            truncate(&r.region, 18)
+        };
+        let island = truncate(&r.main_island, 13);
+
+        let mut line = format!("{:<20} | {:<15} | {:>18}", region, island, fmt_peso(r.total_budget));
+        if reports_per_capita {
+            line += &format!(" | {:>12}", r.budget_per_capita.map(fmt_peso).unwrap_or_default());
+        }
+        line += &format!(" | {:>18}", fmt_peso(r.median_savings));
+        if let Some(weighted) = r.weighted_median_savings {
+            line += &format!(" | {:>18}", fmt_peso(weighted));
+        }
+        line += &format!(
+            " | {:>12} | {:>11.pct$}% | {:>11.pct$}% | {:>12.score$}",
+            fmt_days(r.avg_delay), r.high_delay_pct, r.overrun_rate, r.efficiency_score
+        );
+        if let Some(raw) = r.efficiency_raw {
+            line += &format!(" | {:>12.score$}", raw);
+        }
+        let bar = efficiency_bar(r.efficiency_score, max_score);
+        if show_bars && !bar.is_empty() {
+            line += &format!(" | {}", bar);
+        }
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "{:-<width$}", "")?;
+    if report.iter().any(|r| r.low_sample) {
+        writeln!(
+            out,
+            "* based on fewer than {} projects; interpret with caution",
+            config.min_region_sample
+        )?;
+    }
+    if let (Some(m), Some(min)) = (merged, config.merge_regions_below) {
+        writeln!(
+            out,
+            "Other: {} regions with fewer than {} projects each, combined; listed individually in the export",
+            m.merged.len(),
+            min
+        )?;
+    }
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("report1_regional_summary")))?;
+    Ok(())
+}
+
+/// Character width of a full Report 1 efficiency bar.
+const EFFICIENCY_BAR_WIDTH: usize = 20;
+
+/// Draws `value` as a bar of block characters scaled so that `max` fills the full width.
+fn efficiency_bar(value: f64, max: f64) -> String {
+    const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    if max <= 0.0 || !value.is_finite() || value <= 0.0 {
+        return String::new();
+    }
+    let eighths = ((value / max).min(1.0) * (EFFICIENCY_BAR_WIDTH * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(PARTIAL_BLOCKS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+/// Console colour is on unless --no-color or NO_COLOR asks otherwise, or stdout is not a terminal.
+fn use_color(config: &Config) -> bool {
+    config.color
+        && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        && io::stdout().is_terminal()
+}
+
+/// Wraps an already padded risk flag cell in ANSI red (High Risk) or green (Low Risk).
+fn color_risk_flag(cell: &str, flag: &str) -> String {
+    match flag {
+        "High Risk" => format!("\x1b[31m{}\x1b[0m", cell),
+        "Low Risk" => format!("\x1b[32m{}\x1b[0m", cell),
+        _ => cell.to_string(),
+    }
+}
+
+fn write_report_2(
+    out: &mut impl Write,
+    report: &[FinancialEfficiencies],
+    below_cost_floor: usize,
+    config: &Config,
+    color: bool,
+    pager: &Pager,
+) -> io::Result<()> {
+    let days = decimals(DAYS_DECIMALS);
+    let pct = decimals(PERCENT_DECIMALS);
+    let score = decimals(SCORE_DECIMALS);
+    let mut width = if config.compare_to_average { 273 } else { 240 };
+    if config.recency_decay.is_some() {
+        width += 15;
+    }
+    if config.raw_scores {
+        width += 15;
+    }
+    writeln!(out, "\n{:-<width$}", "")?;
+    writeln!(out, "Report 2: Top Contractors Performance Ranking")?;
+    writeln!(
+        out,
+        "(Top 15 by {}, >={} Projects; {})",
+        config.rank_by.label(),
+        config.min_projects,
+        config.missing_delay.label()
+    )?;
+    write_filter_notes(out, config)?;
+    write_delay_cap_note(out, config)?;
+    if let Some(floor) = config.min_total_cost.filter(|&floor| floor > 0.0) {
+        writeln!(
+            out,
+            "(Total cost floor {}: {} contractors with >={} projects excluded)",
+            fmt_peso(floor),
+            below_cost_floor,
+            config.min_projects
+        )?;
+    }
+    writeln!(
+        out,
+        "(Savings Flag: savings below {}% or above {}% of the approved budget)",
+        config.savings_flag_low, config.savings_flag_high
+    )?;
+    if let Some(decay) = config.recency_decay {
+        writeln!(
+            out,
+            "(Recent Rel.: reliability with each project weighted by {}^({} - funding year))",
+            decay,
+            FUNDING_YEARS.end()
+        )?;
+    }
+    writeln!(out, "{:-<width$}", "")?;
+    let mut header = format!(
+        "{:<5} | {:<40} | {:>18} | {:>10} | {:>10} | {:>18} | {:>12} | {:>18} | {:>10} | {:>18} | {:>12} | {:<10} | {:<12} | {:<12}",
+        "Rank",
+        "Contractor",
+        "Total Cost",
+        "Share %",
+        "Projects",
+        "Avg Cost",
+        "Avg Delay",
+        "Total Savings",
+        "Savings %",
+        "Savings/Day Late",
+        "Reliability",
+        "Risk Flag",
+        "Savings Flag",
+        "Trend"
+    );
+    if config.compare_to_average {
+        header += &format!(" | {:>13} | {:>14}", "Delay vs Avg", "Savings vs Avg");
+    }
+    if config.recency_decay.is_some() {
+        header += &format!(" | {:>12}", "Recent Rel.");
+    }
+    if config.raw_scores {
+        header += &format!(" | {:>12}", "Raw Rel.");
+    }
+    writeln!(out, "{}", header)?;
+    writeln!(out, "{:-<width$}", "")?;
+    
+    // Only print the top 15, matching the CSV output
+    let shown = &report[..report.len().min(REPORT_2_TOP_N)];
+    for (i, r) in shown.iter().enumerate() {
+        pager.before_row(out, i, shown.len())?;
+        let contractor_name = truncate(&r.contractor, 38);
+        // Pad before colouring so the escape codes don't count towards the column width
+        let mut risk_cell = format!("{:<10}", r.risk_flag);
+        if color {
+            risk_cell = color_risk_flag(&risk_cell, &r.risk_flag);
+        }
+
+        let mut line = format!(
+            "{:<5} | {:<40} | {:>18} | {:>9.pct$}% | {:>10} | {:>18} | {:>12} | {:>18} | {:>9.pct$}% | {:>18} | {:>12.score$} | {:<10} | {:<12} | {:<12}",
+            r.rank,
+            contractor_name,
+            fmt_peso(r.total_cost),
+            r.market_share_pct,
+            r.num_projects,
+            fmt_peso(r.avg_project_cost),
+            fmt_days(r.avg_delay),
+            fmt_peso(r.total_savings),
+            r.savings_rate_pct,
+            fmt_peso(r.savings_per_delay_day),
+            r.reliability_index,
+            risk_cell,
+            r.savings_anomaly_flag,
+            r.trend
+        );
+        if let Some(savings_delta) = r.savings_rate_vs_dataset {
+            let delay_delta = r.avg_delay_vs_dataset.map_or("N/A".to_string(), |d| format!("{:+.days$}", d));
+            line += &format!(" | {:>13} | {:>+13.pct$}%", delay_delta, savings_delta);
+        }
+        if let Some(recent) = r.recent_reliability_index {
+            line += &format!(" | {:>12.score$}", recent);
+        }
+        if let Some(raw) = r.reliability_raw {
+            line += &format!(" | {:>12.score$}", raw);
+        }
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "{:-<width$}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("report2_contractor_ranking")))?;
+    Ok(())
+}
+
+fn write_filter_notes(out: &mut impl Write, config: &Config) -> io::Result<()> {
+    if !config.work_types.is_empty() {
+        writeln!(out, "(Type of work: {})", config.work_types.join(", "))?;
+    }
+    if config.outliers_trimmed {
+        writeln!(out, "(Outliers trimmed: {} outside the 1.5 x IQR fences removed)", config.outlier_metric.label())?;
+    }
+    if let Some(year) = config.partition_year {
+        writeln!(out, "(Funding year {} only)", year)?;
+    }
+    if config.start_window.is_active() {
+        writeln!(out, "(Projects with {})", config.start_window.label())?;
+    }
+    Ok(())
+}
+
+fn write_delay_cap_note(out: &mut impl Write, config: &Config) -> io::Result<()> {
+    if let (Some(percentile), Some(cap)) = (config.delay_cap_percentile, config.delay_cap) {
+        writeln!(out, "(Delays capped at {} days, the {}th percentile, before averaging)", cap, percentile)?;
+    }
+    Ok(())
+}
+
+fn write_contractor_reach(out: &mut impl Write, report: &[ContractorReach], config: &Config) -> io::Result<()> {
+    writeln!(out, "\n{:-<100}", "")?;
+    writeln!(out, "Contractor Geographic Reach")?;
+    writeln!(out, "(Top 15 by Number of Distinct Regions)")?;
+    writeln!(out, "{:-<100}", "")?;
+    writeln!(
+        out,
+        "{:<50} | {:>8} | {:>10} | {:>20}",
+        "Contractor", "Regions", "Projects", "Total Cost"
+    )?;
+    writeln!(out, "{:-<100}", "")?;
+
+    // The CSV holds every contractor
+    for r in report.iter().take(15) {
+        let contractor_name = truncate(&r.contractor, 48);
+
+        writeln!(
+            out,
+            "{:<50} | {:>8} | {:>10} | {:>20}",
+            contractor_name,
+            r.region_count,
+            r.total_projects,
+            fmt_peso(r.total_cost)
+        )?;
+    }
+    writeln!(out, "{:-<100}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("contractor_reach")))?;
+    Ok(())
+}
+
+fn write_contractor_churn(
+    out: &mut impl Write,
+    report: &[ContractorChurn],
+    contractors_by_year: &BTreeMap<i32, usize>,
+    config: &Config,
+) -> io::Result<()> {
+    writeln!(out, "\n{:-<110}", "")?;
+    writeln!(out, "Contractor Entry and Exit")?;
+    match churn_span(contractors_by_year) {
+        Some((first, last)) => writeln!(out, "(Exits: active in {} but not {}; entrants: the reverse)", first, last)?,
+        None => writeln!(out, "(Needs projects from at least two funding years)")?,
+    }
+    let per_year: Vec<String> = contractors_by_year
+        .iter()
+        .map(|(year, count)| format!("{}: {}", year, count))
+        .collect();
+    writeln!(out, "(Active contractors by year: {})", per_year.join(", "))?;
+    writeln!(out, "{:-<110}", "")?;
+    writeln!(
+        out,
+        "{:<50} | {:<8} | {:<16} | {:>10} | {:>20}",
+        "Contractor", "Status", "Active Years", "Projects", "Total Cost"
+    )?;
+    writeln!(out, "{:-<110}", "")?;
+
+    // Top 15 of each by total cost; the CSV holds every contractor
+    for status in ["exit", "entrant"] {
+        for r in report.iter().filter(|r| r.status == status).take(15) {
+            writeln!(
+                out,
+                "{:<50} | {:<8} | {:<16} | {:>10} | {:>20}",
+                truncate(&r.contractor, 48),
+                r.status,
+                r.active_years,
+                r.total_projects,
+                fmt_peso(r.total_cost)
+            )?;
+        }
+    }
+    writeln!(out, "{:-<110}", "")?;
+    let exits = report.iter().filter(|r| r.status == "exit").count();
+    writeln!(out, "{} exits, {} entrants", exits, report.len() - exits)?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("contractor_churn")))?;
+    Ok(())
+}
+
+fn write_near_miss_contractors(out: &mut impl Write, report: &[NearMissContractor], config: &Config) -> io::Result<()> {
+    writeln!(out, "\n{:-<80}", "")?;
+    writeln!(out, "Contractors Just Below the Report 2 Threshold")?;
+    writeln!(
+        out,
+        "(Top 15 with {}-{} Projects, by Total Contract Cost)",
+        config.near_miss_min,
+        config.near_miss_upper()
+    )?;
+    writeln!(out, "{:-<80}", "")?;
+    writeln!(out, "{:<50} | {:>8} | {:>18}", "Contractor", "Projects", "Total Cost")?;
+    writeln!(out, "{:-<80}", "")?;
+
+    // Keep the console list short; the CSV holds the full band
+    for r in report.iter().take(15) {
+        let contractor_name = truncate(&r.contractor, 48);
+
+        writeln!(
+            out,
+            "{:<50} | {:>8} | {:>18}",
+            contractor_name,
+            r.num_projects,
+            fmt_peso(r.total_cost)
+        )?;
+    }
+    writeln!(out, "{:-<80}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("report2_near_miss_contractors")))?;
+    Ok(())
+}
+
+/// How many of the largest rank moves the text report lists.
+const RANKING_MOVERS_SHOWN: usize = 5;
+
+fn write_ranking_changes(out: &mut impl Write, changes: &[RankingChange], config: &Config) -> io::Result<()> {
+    let pct = decimals(PERCENT_DECIMALS);
+    let days = decimals(DAYS_DECIMALS);
+    let rank = |r: Option<i32>| r.map_or_else(|| "unranked".to_string(), |r| format!("#{}", r));
+    writeln!(out, "\n{:-<100}", "")?;
+    writeln!(out, "Report 2 Changes Since the Previous Run")?;
+    writeln!(out, "(Compared with {})", config.compare_ranking.as_deref().unwrap_or_default())?;
+    writeln!(out, "{:-<100}", "")?;
+
+    let named = |status: &str| changes.iter().filter(|c| c.status == status).collect::<Vec<_>>();
+    let new_entrants = named("New");
+    writeln!(out, "New in the top 15 ({}):", new_entrants.len())?;
+    for c in &new_entrants {
+        writeln!(out, "  {:<50} now {}", truncate(&c.contractor, 48), rank(c.current_rank))?;
+    }
+    let dropped = named("Dropped");
+    writeln!(out, "Dropped out of the top 15 ({}):", dropped.len())?;
+    for c in &dropped {
+        writeln!(
+            out,
+            "  {:<50} was {}, now {}",
+            truncate(&c.contractor, 48),
+            rank(c.previous_rank),
+            rank(c.current_rank)
+        )?;
+    }
+
+    let mut movers: Vec<_> = changes
+        .iter()
+        .filter(|c| c.status == "Up" || c.status == "Down")
+        .collect();
+    movers.sort_by_key(|c| Reverse(c.rank_delta.unwrap_or(0).abs()));
+    writeln!(out, "Biggest movers:")?;
+    if movers.is_empty() {
+        writeln!(out, "  (none)")?;
+    }
+    for c in movers.iter().take(RANKING_MOVERS_SHOWN) {
+        writeln!(
+            out,
+            "  {:<50} {} -> {} ({:+}) | Savings % {:+.pct$} pts | Avg Delay {}",
+            truncate(&c.contractor, 48),
+            rank(c.previous_rank),
+            rank(c.current_rank),
+            c.rank_delta.unwrap_or(0),
+            c.savings_rate_delta.unwrap_or(0.0),
+            c.avg_delay_delta.map_or("N/A".to_string(), |d| format!("{:+.days$} days", d))
+        )?;
+    }
+    writeln!(out, "{:-<100}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("report2_ranking_changes")))?;
+    Ok(())
+}
+
+fn write_budget_tiers(out: &mut impl Write, report: &[BudgetTierAnalysis], config: &Config) -> io::Result<()> {
+    let pct = decimals(PERCENT_DECIMALS);
+    writeln!(out, "\n{:-<90}", "")?;
+    writeln!(out, "Savings Efficiency by Budget Tier")?;
+    writeln!(out, "(All Projects Bucketed by Approved Budget)")?;
+    writeln!(out, "{:-<90}", "")?;
+    writeln!(
+        out,
+        "{:<12} | {:>10} | {:>22} | {:>16} | {:>12}",
+        "Tier", "Projects", "Total Budget", "Avg Savings %", "Overrun %"
+    )?;
+    writeln!(out, "{:-<90}", "")?;
+
+    for r in report {
+        writeln!(
+            out,
+            "{:<12} | {:>10} | {:>22} | {:>15.pct$}% | {:>11.pct$}%",
+            r.budget_tier,
+            r.project_count,
+            fmt_peso(r.total_budget),
+            r.avg_savings_rate,
+            r.overrun_rate
+        )?;
+    }
+    writeln!(out, "{:-<90}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("budget_tier_analysis")))?;
+    Ok(())
+}
+
+fn write_region_budget_yoy(out: &mut impl Write, report: &[RegionBudgetYoy], config: &Config) -> io::Result<()> {
+    let pct = decimals(PERCENT_DECIMALS);
+    writeln!(out, "\n{:-<84}", "")?;
+    writeln!(out, "Regional Budget Growth")?;
+    writeln!(out, "(Total Approved Budget by Region and FundingYear; YoY vs. the previous year)")?;
+    write_context_years_note(out, config)?;
+    writeln!(out, "{:-<84}", "")?;
+    writeln!(
+        out,
+        "{:<34} | {:>6} | {:>22} | {:>12}",
+        "Region", "Year", "Total Budget", "YoY Change %"
+    )?;
+    writeln!(out, "{:-<84}", "")?;
+
+    for r in report {
+        writeln!(
+            out,
+            "{:<34} | {:>6} | {:>22} | {:>11.pct$}%",
+            truncate(&r.region, 32),
+            r.funding_year,
+            fmt_peso(r.total_budget),
+            r.yoy_change
+        )?;
+    }
+    writeln!(out, "{:-<84}", "")?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("region_budget_yoy")))?;
+    Ok(())
+}
+
+/// Notes that the first year's YoY compares against context-only data.
+fn write_context_years_note(out: &mut impl Write, config: &Config) -> io::Result<()> {
+    if config.context_years > 0 {
+        writeln!(
+            out,
+            "(YoY for {} compares against {} context data, which is not in the rows or totals)",
+            FUNDING_YEARS.start(),
+            config.context_years_label()
+        )?;
+    }
+    Ok(())
+}
+
+fn write_report_3(out: &mut impl Write, report: &[PerformanceMetrics], config: &Config, pager: &Pager) -> io::Result<()> {
+    let pct = decimals(PERCENT_DECIMALS);
+    writeln!(out, "\n{:-<168}", "")?;
+    writeln!(out, "Report 3: Annual Project Type Cost Overrun Trends")?;
+    writeln!(out, "(Grouped by FundingYear and TypeOfWork; {})", config.overrun_margin.label())?;
+    write_filter_notes(out, config)?;
+    write_context_years_note(out, config)?;
+    writeln!(out, "{:-<168}", "")?;
+    writeln!(
+        out,
+        "{:<6} | {:<45} | {:>10} | {:<8} | {:>18} | {:>18} | {:>12} | {:>13} | {:>12}",
+        "Year",
+        "Type of Work",
+        "Projects",
+        "Sample",
+        "Total Savings",
+        "Avg Savings",
+        "Overrun %",
+        "Utilization %",
+        "YoY Change %"
+    )?;
+    writeln!(out, "{:-<168}", "")?;
+    
+    for (i, r) in report.iter().enumerate() {
+        pager.before_row(out, i, report.len())?;
+        let type_of_work = truncate(&r.type_of_work, 43);
+
+        writeln!(
+            out,
+            "{:<6} | {:<45} | {:>10} | {:<8} | {:>18} | {:>18} | {:>12.pct$}% | {:>12.pct$}% | {:>12.pct$}%",
+            r.funding_year,
+            type_of_work,
+            r.total_projects,
+            r.sample_adequacy,
+            fmt_peso(r.total_savings),
+            fmt_peso(r.avg_savings),
+            r.overrun_rate,
+            r.avg_utilization_pct,
+            r.yoy_change
+        )?;
+    }
+    writeln!(out, "{:-<168}", "")?;
+    writeln!(
+        out,
+        "Sample: sparse below {} projects, limited below {}, adequate from {}",
+        config.limited_sample_min, config.adequate_sample_min, config.adequate_sample_min
+    )?;
+    writeln!(out, "Table exported to {}", config.table_format.file_names(&config.output_name("report3_annual_trends")))?;
+    Ok(())
+}
+
+/// Formats an average delay for console display, or "N/A" for a group with no delay data,
+/// which would otherwise read as 0 days (on time).
+pub fn fmt_days(days: Option<f64>) -> String {
+    days.map_or_else(|| "N/A".to_string(), |d| format!("{:.*}", decimals(DAYS_DECIMALS), d))
+}
+
+/// Formats a peso amount for console display, e.g. 1234567.891 -> "1,234,567.89".
+/// CSV exports keep plain numbers.
+pub fn fmt_peso(v: f64) -> String {
+    if !v.is_finite() {
+        return v.to_string();
+    }
+    let digits = format!("{:.*}", decimals(MONEY_DECIMALS), v.abs());
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((&digits, ""));
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, c) in int_part.chars().enumerate() {
+        if i > 0 && (int_part.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+
+    // Values that round to zero are shown without a sign
+    let is_negative = v < 0.0 && digits.bytes().any(|b| matches!(b, b'1'..=b'9'));
+    let sign = if is_negative { "-" } else { "" };
+    if frac_part.is_empty() {
+        format!("{}{}", sign, grouped)
+    } else {
+        format!("{}{}.{}", sign, grouped, frac_part)
+    }
+}
+
+/// Keeps projects whose type of work contains any of the terms (case-insensitive).
+pub fn filter_by_work_type(projects: &[Project], terms: &[String]) -> Vec<Project> {
+    let terms: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+    projects
+        .iter()
+        .filter(|p| matches_work_type(p, &terms))
+        .cloned()
+        .collect()
+}
+
+/// True when the project's type of work contains any of the already-lowercased terms.
+pub fn matches_work_type(project: &Project, lowercase_terms: &[String]) -> bool {
+    let work_type = project.type_of_work.to_lowercase();
+    lowercase_terms.iter().any(|t| work_type.contains(t.as_str()))
+}
+
+/// Blank fields are only counted in columns the file has: one it lacks reads as blank after
+/// `ColumnMapping::standardize`, and would otherwise reject every row.
+fn has_blank_field(record: &StringRecord, mapping: &ColumnMapping) -> bool {
+    record
+        .iter()
+        .enumerate()
+        .any(|(i, f)| mapping.has_source_column(i) && f.trim().is_empty())
+}
+
+fn has_blank_critical_field(record: &StringRecord) -> bool {
+    CRITICAL_COLUMNS
+        .iter()
+        .any(|(index, _)| record.get(*index).is_none_or(|f| f.trim().is_empty()))
+}
+
+/// Whether the blank-value filter rejects `record`: any blank field by default, or only a blank
+/// critical field when `keep_partial` is set. A critical column the file lacks always counts as blank.
+fn is_blank_rejected(record: &StringRecord, mapping: &ColumnMapping, keep_partial: bool) -> bool {
+    if keep_partial {
+        has_blank_critical_field(record)
+    } else {
+        has_blank_critical_field(record) || has_blank_field(record, mapping)
+    }
+}
+
+/// Reads a text column, substituting `BLANK_FIELD_PLACEHOLDER` when it is blank.
+fn text_field(record: &StringRecord, index: usize) -> String {
+    match record.get(index).map(str::trim) {
+        Some(value) if !value.is_empty() => value.to_string(),
+        _ => BLANK_FIELD_PLACEHOLDER.to_string(),
+    }
+}
+
+/// Optional bounds (inclusive) on a project's start date, applied on top of the funding-year filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct StartDateWindow {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl StartDateWindow {
+    pub fn is_active(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    /// Whether a start date passes; with an active window, a missing date never does.
+    pub fn contains(&self, start_date: Option<NaiveDate>) -> bool {
+        match start_date {
+            Some(date) => self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to),
+            None => !self.is_active(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let bound = |date: Option<NaiveDate>| date.map_or("any".to_string(), |d| d.to_string());
+        format!("start date {} to {}", bound(self.from), bound(self.to))
+    }
+}
+
+fn parse_data(
+    record: &StringRecord,
+    mapping: &ColumnMapping,
+    keep_partial: bool,
+    start_window: StartDateWindow,
+    years: std::ops::RangeInclusive<i32>,
+) -> Result<Option<Project>, Box<dyn Error>> {
+    let date_format = "%Y-%m-%d";
+
+    // REQ-0003: Filter for "Blank Values"
+    if is_blank_rejected(record, mapping, keep_partial) {
+        return Ok(None); // Skip row if a (critical) field is blank
+    }
+
+    // Parse funding_year (col 9)
+    let raw_year = record.get(9).ok_or("Missing funding_year at col 9")?.trim();
+    let funding_year: i32 = raw_year
+        .parse()
+        .map_err(|e| format!("funding_year (col 9) \"{}\": {}", raw_year, e))?;
+
+    // REQ-0003: Filter for 2021-2023 (plus any context years)
+    if !years.contains(&funding_year) {
+        return Ok(None); // Skip row if not in year range
+    }
+
+    // Parse Financials (removing thousands separators)
+    let approved_budget = parse_money(record.get(11).ok_or("Missing approved_budget at col 11")?)
+        .map_err(|e| format!("approved_budget (col 11): {}", e))?;
+    let contract_cost = parse_money(record.get(12).ok_or("Missing contract_cost at col 12")?)
+        .map_err(|e| format!("contract_cost (col 12): {}", e))?;
+
+    // REQ-0004: Compute Derived Fields
+    let cost_savings = (approved_budget - contract_cost).to_pesos();
+    let (approved_budget, contract_cost) = (approved_budget.to_pesos(), contract_cost.to_pesos());
+
+    // Dates
+    let start_str = record.get(16).unwrap_or("").trim();
+    let end_str = record.get(13).unwrap_or("").trim();
+    let start_date = NaiveDate::parse_from_str(start_str, date_format).ok();
+    let end_date = NaiveDate::parse_from_str(end_str, date_format).ok();
+    let completion_delay_days = match (start_date, end_date) {
+        (Some(s), Some(e)) => Some((e - s).num_days()),
+        _ => None,
+    };
+
+    // Optional start-date window; rows without a parseable start date fail an active one
+    if !start_window.contains(start_date) {
+        return Ok(None);
+    }
+
+    let project = Project {
+        project_id: record.get(6).unwrap_or("").trim().to_string(),
+        start_date,
+        end_date,
+        main_island: text_field(record, 0),
+        region: text_field(record, 1),
+        province: record.get(PROVINCE_COLUMN.0).unwrap_or("").trim().to_string(),
+        type_of_work: text_field(record, 8),
+        contractor: text_field(record, 14),
+        funding_year,
+        approved_budget,
+        contract_cost,
+        cost_savings,
+        completion_delay_days,
+    };
+
+    Ok(Some(project))
+}
+
+pub struct ValidationResult {
+    pub missing_columns: Vec<String>,
+    pub rows_sampled: usize,
+    pub rows_parsed: usize,
+    pub rows_filtered: usize,
+    pub rows_failed: usize,
+}
+
+impl ValidationResult {
+    /// Passes when every required column is present and at most 10% of sampled rows fail to parse.
+    pub fn passed(&self) -> bool {
+        self.missing_columns.is_empty() && self.rows_failed * 10 <= self.rows_sampled
+    }
+}
+
+/// Opens a dataset file, or standard input for `STDIN_PATH`.
+fn open_dataset(file_path: &str) -> io::Result<Box<dyn io::Read>> {
+    if file_path == STDIN_PATH {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(File::open(file_path)?))
+    }
+}
+
+/// Checks the header against `REQUIRED_COLUMNS` and parses a small sample of rows.
+pub fn validate_dataset(file_path: &str) -> Result<ValidationResult, Box<dyn Error>> {
+    let file = open_dataset(file_path)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(file);
+
+    let mapping = ColumnMapping::new(reader.headers()?);
+    let missing_columns = REQUIRED_COLUMNS
+        .iter()
+        .filter(|(_, name)| mapping.column(name).is_none())
+        .map(|(index, name)| format!("{} (col {})", name, index))
+        .collect();
+
+    let mut result = ValidationResult {
+        missing_columns,
+        rows_sampled: 0,
+        rows_parsed: 0,
+        rows_filtered: 0,
+        rows_failed: 0,
+    };
+    for record in reader.records().take(VALIDATION_SAMPLE_ROWS) {
+        result.rows_sampled += 1;
+        let record = record.map(|r| if mapping.is_standard() { r } else { mapping.standardize(&r) });
+        match record.map_err(Box::<dyn Error>::from).and_then(|r| parse_data(&r, &mapping, false, StartDateWindow::default(), FUNDING_YEARS)) {
+            Ok(Some(_)) => result.rows_parsed += 1,
+            Ok(None) => result.rows_filtered += 1,
+            Err(_) => result.rows_failed += 1,
+        }
+    }
+    Ok(result)
+}
+
+/// A peso amount as a whole number of centavos. Money totals are summed in this form so
+/// they come out exact instead of collecting f64 rounding error over thousands of rows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Centavos(i64);
+
+impl Centavos {
+    /// Rounds to the nearest centavo. Exact for any amount read by `parse_money`.
+    pub fn from_pesos(pesos: f64) -> Self {
+        Centavos((pesos * 100.0).round() as i64)
+    }
+
+    pub fn to_pesos(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Reads a plain decimal such as "-1234.565" exactly, rounding half away from zero past
+    /// the second decimal place. None for anything else, including scientific notation.
+    fn parse_decimal(s: &str) -> Option<Self> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() || !whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+        let mut fraction = fraction.bytes().map(|b| i64::from(b - b'0')).chain(std::iter::repeat(0));
+        let (tenths, hundredths, rest) = (fraction.next()?, fraction.next()?, fraction.next()?);
+        let centavos = whole
+            .checked_mul(100)?
+            .checked_add(tenths * 10 + hundredths + i64::from(rest >= 5))?;
+        Some(Centavos(if negative { -centavos } else { centavos }))
+    }
+}
+
+impl std::ops::Add for Centavos {
+    type Output = Centavos;
+
+    fn add(self, other: Centavos) -> Centavos {
+        Centavos(self.0 + other.0)
+    }
+}
+
+impl std::ops::AddAssign for Centavos {
+    fn add_assign(&mut self, other: Centavos) {
+        self.0 += other.0;
+    }
+}
+
+impl std::ops::Sub for Centavos {
+    type Output = Centavos;
+
+    fn sub(self, other: Centavos) -> Centavos {
+        Centavos(self.0 - other.0)
+    }
+}
+
+impl std::iter::Sum for Centavos {
+    fn sum<I: Iterator<Item = Centavos>>(iter: I) -> Centavos {
+        iter.fold(Centavos::default(), |total, v| total + v)
+    }
+}
+
+/// Sums peso amounts in whole centavos, so the total is exact where a plain f64 sum drifts.
+///
+/// ```
+/// let amounts = vec![0.1; 1_000_000];
+/// assert_ne!(amounts.iter().sum::<f64>(), 100_000.0);
+/// assert_eq!(mco2_rust::sum_money(amounts), 100_000.0);
+/// ```
+pub fn sum_money(amounts: impl IntoIterator<Item = f64>) -> f64 {
+    amounts.into_iter().map(Centavos::from_pesos).sum::<Centavos>().to_pesos()
+}
+
+/// Parses a peso amount, ignoring comma, space and apostrophe thousands separators.
+/// The decimal point and sign are left untouched. Scientific notation ("1.2E9", "1.2e+9")
+/// is accepted only without separators: stripping the comma from a decimal-comma value
+/// like "1,2E9" would silently read it as 1.2E10. Amounts are kept to the centavo.
+fn parse_money(raw: &str) -> Result<Centavos, Box<dyn Error>> {
+    let is_separator = |c: char| matches!(c, ',' | ' ' | '\'' | '\u{a0}' | '\u{2019}');
+    let trimmed = raw.trim();
+    if trimmed.contains(['e', 'E']) && trimmed.contains(is_separator) {
+        return Err(format!("Invalid amount '{}': separators are not allowed in scientific notation", trimmed).into());
+    }
+    let cleaned: String = trimmed.chars().filter(|&c| !is_separator(c)).collect();
+    if let Some(amount) = Centavos::parse_decimal(&cleaned) {
+        return Ok(amount);
+    }
+    let pesos: f64 = cleaned
+        .parse()
+        .map_err(|e| format!("Invalid amount '{}': {}", trimmed, e))?;
+    if !pesos.is_finite() {
+        return Err(format!("Invalid amount '{}': not a finite number", trimmed).into());
+    }
+    Ok(Centavos::from_pesos(pesos))
+}
+
+/// Returns the canonical main island for a region, if the region is known.
+fn canonical_island(region: &str) -> Option<&'static str> {
+    REGION_ISLANDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(region))
+        .map(|(_, island)| *island)
+}
+
+/// Overrides `main_island` with the canonical island for the region.
+/// Returns true when the label was changed.
+fn correct_island(project: &mut Project) -> bool {
+    match canonical_island(&project.region) {
+        Some(island) if project.main_island != island => {
+            project.main_island = island.to_string();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Swaps the start and completion dates when completion comes before start, on the theory
+/// that the two columns were mixed up, and recomputes the delay. Returns true when swapped.
+fn swap_reversed_dates(project: &mut Project) -> bool {
+    match (project.start_date, project.end_date) {
+        (Some(start), Some(end)) if end < start => {
+            project.start_date = Some(end);
+            project.end_date = Some(start);
+            project.completion_delay_days = Some((start - end).num_days());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Totals from the `--context-years` projects. They never appear in a report row or total;
+/// they only stand in for the missing previous year when a YoY change is computed.
+#[derive(Debug, Clone, Default)]
+pub struct YoyBaselines {
+    /// (sum of cost_savings, project count) per (funding year, type of work)
+    savings: HashMap<(i32, String), (Centavos, usize)>,
+    /// Approved budget per (region, funding year)
+    region_budgets: HashMap<(String, i32), Centavos>,
+}
+
+impl YoyBaselines {
+    pub fn from_projects<'a>(projects: impl IntoIterator<Item = &'a Project>) -> Self {
+        let mut baselines = YoyBaselines::default();
+        for p in projects {
+            baselines.add(p);
+        }
+        baselines
+    }
+
+    fn add(&mut self, p: &Project) {
+        let entry = self
+            .savings
+            .entry((p.funding_year, p.type_of_work.clone()))
+            .or_default();
+        entry.0 += Centavos::from_pesos(p.cost_savings);
+        entry.1 += 1;
+        *self
+            .region_budgets
+            .entry((p.region.clone(), p.funding_year))
+            .or_default() += Centavos::from_pesos(p.approved_budget);
+    }
+
+    /// Average savings for a Report 3 cell in a context year.
+    fn avg_savings(&self, year: i32, type_of_work: &str) -> Option<f64> {
+        self.savings
+            .get(&(year, type_of_work.to_string()))
+            .map(|(sum, count)| sum.to_pesos() / *count as f64)
+    }
+
+    fn region_budget(&self, region: &str, year: i32) -> Option<f64> {
+        self.region_budgets.get(&(region.to_string(), year)).map(|budget| budget.to_pesos())
+    }
+}
+
+/// Kept projects counted by the sign of their completion delay.
+#[derive(Debug, Default)]
+pub struct DelayBreakdown {
+    pub late: usize,
+    pub on_time: usize,
+    /// Completed before the start date, or the dates are reversed
+    pub early: usize,
+    pub missing: usize,
+}
+
+impl DelayBreakdown {
+    fn add(&mut self, delay: Option<i64>) {
+        match delay {
+            Some(d) if d > 0 => self.late += 1,
+            Some(0) => self.on_time += 1,
+            Some(_) => self.early += 1,
+            None => self.missing += 1,
+        }
+    }
+}
+
+/// Row counts gathered while loading the dataset.
+#[derive(Debug, Default)]
+pub struct LoadSummary {
+    pub record_count: usize,
+    pub skipped_count: usize,
+    pub kept_count: usize,
+    pub islands_corrected: usize,
+    /// Rows whose start and completion dates were swapped back by `--fix-swapped-dates`
+    pub dates_swapped: usize,
+    /// Kept 2021-2023 projects by final delay, after any swap or future-date discard
+    pub delays: DelayBreakdown,
+    pub future_end_dates: usize,
+    pub duplicates_skipped: usize,
+    /// Rows whose field count differs from the header's
+    pub malformed_rows: usize,
+    pub blank_field_rows: usize,
+    /// Rows with a blank non-critical field kept by `--keep-partial-rows`
+    pub partial_rows_kept: usize,
+    pub out_of_range_rows: usize,
+    /// Rows from the `--context-years` before 2021, kept apart from `kept_count`
+    pub context_rows: usize,
+    /// Those rows' projects; filled by `load_data` only, so the main projects stay 2021-2023
+    pub context_projects: Vec<Project>,
+    /// Rows in 2021-2023 whose start date is missing or outside `--start-date-from/--start-date-to`
+    pub outside_start_window_rows: usize,
+    /// Out-of-range rows whose year is not plausible at all, a sign of a shifted column
+    pub implausible_year_rows: usize,
+    pub implausible_year_example: Option<String>,
+    pub parse_error_rows: usize,
+    pub files: Vec<FileLoadCount>,
+    /// Set when Ctrl-C stopped the load early
+    pub interrupted: bool,
+    pub dataset_sha256: String,
+}
+
+/// Feeds every byte read through to a SHA-256 hasher, fingerprinting the input as it loads.
+struct HashingReader<'a, R> {
+    inner: R,
+    hasher: &'a mut Sha256,
+}
+
+impl<R: io::Read> io::Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Per-file row counts when several datasets are loaded together.
+#[derive(Debug)]
+pub struct FileLoadCount {
+    pub path: String,
+    pub record_count: usize,
+    pub kept_count: usize,
+}
+
+/// Reads the datasets, stopping after `config.row_limit` source rows when one is given.
+/// Context-year projects go to `LoadSummary::context_projects`, not the returned list.
+pub fn load_data(paths: &[String], config: &Config) -> Result<(LoadSummary, Vec<Project>), Box<dyn Error>> {
+    let mut projects: Vec<Project> = Vec::new();
+    let mut context_projects: Vec<Project> = Vec::new();
+    let mut summary = load_data_with(paths, config, |project| {
+        if FUNDING_YEARS.contains(&project.funding_year) {
+            projects.push(project);
+        } else {
+            context_projects.push(project);
+        }
+    })?;
+    summary.context_projects = context_projects;
+    Ok((summary, projects))
+}
+
+/// Parses each dataset row by row, handing every kept project to `on_project`, including
+/// context-year ones, which callers must keep out of the analysis.
+/// Rows whose ContractId already appeared in an earlier file are skipped as duplicates.
+pub fn load_data_with(
+    paths: &[String],
+    config: &Config,
+    mut on_project: impl FnMut(Project),
+) -> Result<LoadSummary, Box<dyn Error>> {
+    let started = std::time::Instant::now();
+    let mut summary = LoadSummary::default();
+    let mut earlier_contract_ids: HashSet<String> = HashSet::new();
+    let mut hasher = Sha256::new();
+    for path in paths {
+        let records_before = summary.record_count;
+        let kept_before = summary.kept_count;
+        let contract_ids = load_file(path, config, &earlier_contract_ids, &mut hasher, &mut summary, &mut on_project)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        earlier_contract_ids.extend(contract_ids);
+        summary.files.push(FileLoadCount {
+            path: path.clone(),
+            record_count: summary.record_count - records_before,
+            kept_count: summary.kept_count - kept_before,
+        });
+        if summary.interrupted {
+            break;
+        }
+    }
+    summary.dataset_sha256 = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    log::info!(
+        "Read {} rows ({} kept) from {} file(s) in {:.2?}",
+        summary.record_count,
+        summary.kept_count,
+        paths.len(),
+        started.elapsed()
+    );
+    if !config.quiet {
+        println!("Skipped {} rows due to filtering or parsing errors...", summary.skipped_count);
+    }
+    Ok(summary)
+}
+
+/// Says which header spellings were read as which standard columns, and which required
+/// columns are missing, so header drift is visible at load time.
+fn report_column_mapping(file_path: &str, mapping: &ColumnMapping, config: &Config) {
+    for (name, header) in mapping.aliases_used() {
+        log::info!("{}: column {} read from header \"{}\"", file_path, name, header);
+    }
+    let unused_missing: Vec<&str> = mapping
+        .missing()
+        .into_iter()
+        .filter(|name| !REQUIRED_COLUMNS.iter().any(|(_, required)| required == name))
+        .collect();
+    if !unused_missing.is_empty() {
+        log::info!("{}: no column for {}; left out of the blank-value check", file_path, unused_missing.join(", "));
+    }
+    if config.quiet {
+        return;
+    }
+    if !mapping.aliases_used().is_empty() {
+        let matched: Vec<String> = mapping
+            .aliases_used()
+            .iter()
+            .map(|(name, header)| format!("{} <- \"{}\"", name, header))
+            .collect();
+        println!("Header aliases in {}: {}", file_path, matched.join(", "));
+    }
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .iter()
+        .map(|(_, name)| *name)
+        .filter(|name| mapping.column(name).is_none())
+        .collect();
+    if !missing.is_empty() {
+        println!("WARNING: {} has no column for {}; those fields read as blank.", file_path, missing.join(", "));
+    }
+}
+
+/// Reads one dataset file into `summary`, returning the ContractIds it contained.
+fn load_file(
+    file_path: &str,
+    config: &Config,
+    earlier_contract_ids: &HashSet<String>,
+    hasher: &mut Sha256,
+    summary: &mut LoadSummary,
+    on_project: &mut impl FnMut(Project),
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let file = HashingReader {
+        inner: open_dataset(file_path)?,
+        hasher,
+    };
+    // Flexible so a row with the wrong field count is reported and skipped instead of
+    // aborting the whole load
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(file);
+    let header_count = reader.headers()?.len();
+    let mapping = ColumnMapping::new(reader.headers()?);
+    let has_province = mapping.column(PROVINCE_COLUMN.1).is_some();
+    report_column_mapping(file_path, &mapping, config);
+    let mut contract_ids = HashSet::new();
+    let latest_plausible_end =
+        chrono::Local::now().date_naive() + chrono::Duration::days(config.future_date_margin_days);
+
+    for result in reader.records() {
+        if was_interrupted() {
+            summary.interrupted = true;
+            break;
+        }
+        if config.row_limit.is_some_and(|limit| summary.record_count >= limit) {
+            break;
+        }
+        let record = result?;
+        summary.record_count += 1;
+
+        // An unquoted comma (e.g. in a contractor name) shifts every later column
+        if record.len() != header_count && config.strict {
+            return Err(format!(
+                "row #{}: expected {} fields, found {} (--strict stops at the first bad row)",
+                summary.record_count,
+                header_count,
+                record.len()
+            )
+            .into());
+        }
+        if record.len() != header_count {
+            log::warn!(
+                "{} row #{}: expected {} fields, found {}; skipped. Raw row: {:?}",
+                file_path,
+                summary.record_count,
+                header_count,
+                record.len(),
+                record.iter().collect::<Vec<_>>().join(",")
+            );
+            summary.malformed_rows += 1;
+            summary.skipped_count += 1;
+            continue;
+        }
+
+        let record = if mapping.is_standard() { record } else { mapping.standardize(&record) };
+        let contract_id = record.get(10).unwrap_or_default().trim();
+        if !contract_id.is_empty() {
+            if earlier_contract_ids.contains(contract_id) {
+                log::debug!("{} row #{}: ContractId {} seen in an earlier file, skipped", file_path, summary.record_count, contract_id);
+                summary.duplicates_skipped += 1;
+                summary.skipped_count += 1;
+                continue;
+            }
+            contract_ids.insert(contract_id.to_string());
+        }
+
+        match parse_data(&record, &mapping, config.keep_partial_rows, config.start_window, config.loaded_years()) {
+            Ok(Some(mut project)) => {
+                if config.keep_partial_rows && has_blank_field(&record, &mapping) {
+                    summary.partial_rows_kept += 1;
+                    log::debug!("{} row #{}: kept despite blank non-critical field", file_path, summary.record_count);
+                }
+                if project.project_id.is_empty() {
+                    project.project_id = format!("row-{}", summary.record_count);
+                }
+                if !has_province {
+                    project.province.clear();
+                }
+                if config.correct_islands && correct_island(&mut project) {
+                    summary.islands_corrected += 1;
+                }
+                if config.fix_swapped_dates && swap_reversed_dates(&mut project) {
+                    summary.dates_swapped += 1;
+                    log::debug!("{} row #{}: start and completion dates swapped", file_path, summary.record_count);
+                }
+                // A completion date well past today is a data error, so its delay is suspect
+                if let Some(end_date) = project.end_date.filter(|d| *d > latest_plausible_end) {
+                    summary.future_end_dates += 1;
+                    log::debug!("{} row #{}: future completion date {}", file_path, summary.record_count, end_date);
+                    if config.null_future_delays {
+                        project.completion_delay_days = None;
+                    }
+                }
+                log::trace!("{} row #{}: kept ({}, {})", file_path, summary.record_count, project.region, project.funding_year);
+                if FUNDING_YEARS.contains(&project.funding_year) {
+                    summary.kept_count += 1;
+                    summary.delays.add(project.completion_delay_days);
+                } else {
+                    summary.context_rows += 1;
+                }
+                on_project(project);
+            }
+            Ok(None) => {
+                summary.skipped_count += 1;
+                if is_blank_rejected(&record, &mapping, config.keep_partial_rows) {
+                    summary.blank_field_rows += 1;
+                    log::debug!("{} row #{}: filtered, blank field", file_path, summary.record_count);
+                } else if record
+                    .get(9)
+                    .and_then(|y| y.trim().parse().ok())
+                    .is_some_and(|y: i32| config.loaded_years().contains(&y))
+                {
+                    // The funding year passed, so the start-date window rejected the row
+                    summary.outside_start_window_rows += 1;
+                    log::debug!(
+                        "{} row #{}: filtered, start date \"{}\" outside the window",
+                        file_path,
+                        summary.record_count,
+                        record.get(16).unwrap_or_default().trim()
+                    );
+                } else {
+                    summary.out_of_range_rows += 1;
+                    let year = record.get(9).unwrap_or_default().trim();
+                    if year.parse::<i32>().is_ok_and(|y| !PLAUSIBLE_FUNDING_YEARS.contains(&y)) {
+                        summary.implausible_year_rows += 1;
+                        summary.implausible_year_example.get_or_insert_with(|| year.to_string());
+                    }
+                    log::debug!(
+                        "{} row #{}: filtered, funding year {} outside 2021-2023",
+                        file_path,
+                        summary.record_count,
+                        record.get(9).unwrap_or_default().trim()
+                    );
+                }
+            }
+            Err(e) if config.strict => {
+                return Err(format!("row #{}: {} (--strict stops at the first bad row)", summary.record_count, e).into());
+            }
+            Err(e) => {
+                log::debug!("{} row #{}: parse error: {} ({:?})", file_path, summary.record_count, e, record);
+                summary.skipped_count += 1;
+                summary.parse_error_rows += 1;
+            }
+        }
+    }
+    // The fingerprint covers the whole file even when --limit stopped reading early
+    if !summary.interrupted {
+        io::copy(&mut reader.into_inner(), &mut io::sink())?;
+    }
+    Ok(contract_ids)
+}
+
+pub const SUMMARY_HISTORY_FILE: &str = "summary_history.csv";
+
+pub struct Reports {
+    pub report1: Vec<InfrastructureTrends>,
+    pub report2: Vec<FinancialEfficiencies>,
+    pub near_miss: Vec<NearMissContractor>,
+    pub reach: Vec<ContractorReach>,
+    pub report3: Vec<PerformanceMetrics>,
+    pub budget_tiers: Vec<BudgetTierAnalysis>,
+    pub region_yoy: Vec<RegionBudgetYoy>,
+    pub churn: Vec<ContractorChurn>,
+    /// Distinct contractors per funding year, shown with the churn table
+    pub contractors_by_year: BTreeMap<i32, usize>,
+    /// Filled only with --compare-ranking
+    pub ranking_changes: Vec<RankingChange>,
+    /// Filled only with --merge-regions-below, outside large-file mode
+    pub report1_merged: Option<MergedRegions>,
+    /// Contractors with enough projects for Report 2 but below the total-cost floor
+    pub report2_below_cost_floor: usize,
+    /// Report 1 regions found in the --population file; None when no file was joined
+    pub population_matched: Option<usize>,
+    pub summary: SummaryJson,
+    /// Number of NaN/infinite metric values replaced with 0.0 by `sanitize`
+    pub non_finite_replaced: usize,
+}
+
+/// One report as plain text cells, for browsing in the interactive explorer.
+#[derive(Debug)]
+pub struct ReportTable {
+    pub title: &'static str,
+    /// Column headings, e.g. "Savings Rate %"
+    pub headers: Vec<String>,
+    /// Cells exactly as the CSV export writes them, so numbers keep their rounding
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ReportTable {
+    fn new<T: Serialize>(title: &'static str, data: &[T]) -> Result<Self, Box<dyn Error>> {
+        let csv_text = delimited_text(data, b',')?;
+        let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+        let headers = reader.headers()?.iter().map(column_heading).collect();
+        let rows = reader
+            .records()
+            .map(|record| Ok(record?.iter().map(str::to_string).collect()))
+            .collect::<Result<_, csv::Error>>()?;
+        Ok(ReportTable { title, headers, rows })
+    }
+}
+
+impl Reports {
+    /// Every non-empty report as a `ReportTable`, Report 2 with all ranked contractors
+    /// rather than just the top 15.
+    pub fn tables(&self) -> Result<Vec<ReportTable>, Box<dyn Error>> {
+        let mut tables = Vec::new();
+        let mut add = |table: ReportTable| {
+            if !table.rows.is_empty() {
+                tables.push(table);
+            }
+        };
+        add(ReportTable::new("Report 1: Regional Flood Mitigation Efficiency Summary", &self.report1)?);
+        add(ReportTable::new("Report 2: Contractor Performance Ranking", &self.report2)?);
+        add(ReportTable::new("Report 3: Annual Project Type Cost Overrun Trends", &self.report3)?);
+        add(ReportTable::new("Contractors Just Below the Report 2 Threshold", &self.near_miss)?);
+        add(ReportTable::new("Report 2 Changes Since the Previous Run", &self.ranking_changes)?);
+        add(ReportTable::new("Contractor Geographic Reach", &self.reach)?);
+        add(ReportTable::new("Contractor Entry and Exit", &self.churn)?);
+        add(ReportTable::new("Savings Efficiency by Budget Tier", &self.budget_tiers)?);
+        add(ReportTable::new("Regional Budget Growth", &self.region_yoy)?);
+        Ok(tables)
+    }
+
+    /// Replaces NaN and infinite metrics with 0.0 so they never reach the CSV/JSON output
+    /// as "NaN" or "inf", counting how many were replaced.
+    fn sanitize(&mut self) {
+        let mut count = 0;
+        let mut fix = |v: &mut f64| {
+            if !v.is_finite() {
+                *v = 0.0;
+                count += 1;
+            }
+        };
+        let merged_row = self.report1_merged.as_mut().map(|m| &mut m.row);
+        for r in self.report1.iter_mut().chain(merged_row) {
+            fix(&mut r.total_budget);
+            if let Some(per_capita) = r.budget_per_capita.as_mut() {
+                fix(per_capita);
+            }
+            fix(&mut r.median_savings);
+            if let Some(weighted) = r.weighted_median_savings.as_mut() {
+                fix(weighted);
+            }
+            if let Some(avg_delay) = r.avg_delay.as_mut() {
+                fix(avg_delay);
+            }
+            fix(&mut r.high_delay_pct);
+            fix(&mut r.overrun_rate);
+            fix(&mut r.efficiency_score);
+            if let Some(raw) = r.efficiency_raw.as_mut() {
+                fix(raw);
+            }
+        }
+        for r in &mut self.report2 {
+            fix(&mut r.total_cost);
+            fix(&mut r.market_share_pct);
+            if let Some(avg_delay) = r.avg_delay.as_mut() {
+                fix(avg_delay);
+            }
+            fix(&mut r.total_savings);
+            fix(&mut r.savings_rate_pct);
+            fix(&mut r.savings_per_delay_day);
+            fix(&mut r.reliability_index);
+            fix(&mut r.avg_project_cost);
+            for delta in [&mut r.reliability_raw, &mut r.recent_reliability_index, &mut r.avg_delay_vs_dataset, &mut r.savings_rate_vs_dataset]
+                .into_iter()
+                .flatten()
+            {
+                fix(delta);
+            }
+        }
+        for r in &mut self.near_miss {
+            fix(&mut r.total_cost);
+        }
+        for r in &mut self.reach {
+            fix(&mut r.total_cost);
+        }
+        for r in &mut self.churn {
+            fix(&mut r.total_cost);
+        }
+        for r in &mut self.report3 {
+            fix(&mut r.total_savings);
+            fix(&mut r.avg_savings);
+            fix(&mut r.overrun_rate);
+            fix(&mut r.avg_utilization_pct);
+            fix(&mut r.yoy_change);
+        }
+        for r in &mut self.region_yoy {
+            fix(&mut r.total_budget);
+            fix(&mut r.yoy_change);
+        }
+        for r in &mut self.ranking_changes {
+            let deltas = [&mut r.total_cost_delta, &mut r.savings_rate_delta, &mut r.avg_delay_delta, &mut r.reliability_delta];
+            for delta in deltas.into_iter().flatten() {
+                fix(delta);
+            }
+        }
+        for r in &mut self.budget_tiers {
+            fix(&mut r.total_budget);
+            fix(&mut r.avg_savings_rate);
+            fix(&mut r.overrun_rate);
+        }
+        fix(&mut self.summary.total_budget_analyzed);
+        if let Some(avg_delay) = self.summary.global_avg_delay.as_mut() {
+            fix(avg_delay);
+        }
+        fix(&mut self.summary.national_efficiency_score);
+        fix(&mut self.summary.total_overrun_amount);
+        fix(&mut self.summary.contractor_hhi);
+        fix(&mut self.summary.budget_gini);
+        for budget in self.summary.budget_by_year.values_mut() {
+            fix(budget);
+        }
+        for island in self.summary.island_breakdown.values_mut() {
+            fix(&mut island.total_budget);
+            fix(&mut island.total_savings);
+            if let Some(avg_delay) = island.avg_delay.as_mut() {
+                fix(avg_delay);
+            }
+        }
+        self.non_finite_replaced = count;
+    }
+
+    /// Fills the Report 1 budget per capita from the --population file. Regions are matched
+    /// by name, ignoring case; unlisted regions keep None.
+    fn join_population(&mut self, config: &Config) {
+        let Some(path) = &config.population_file else { return };
+        if !config.wants(ReportKind::Regional) {
+            return;
+        }
+        let populations = match read_populations(path) {
+            Ok(populations) => populations,
+            Err(e) => {
+                println!("WARNING: Budget per capita skipped; cannot read {}: {}", path, e);
+                return;
+            }
+        };
+        let population = |region: &str| populations.get(&region.trim().to_lowercase()).copied();
+        for r in &mut self.report1 {
+            r.budget_per_capita = population(&r.region).map(|n| r.total_budget / n);
+        }
+        self.population_matched = Some(self.report1.iter().filter(|r| r.budget_per_capita.is_some()).count());
+        if let Some(m) = &mut self.report1_merged {
+            // Only when every merged region has a population, or the ratio would be inflated
+            let regions: HashSet<&str> = m.merged.iter().map(|(region, _)| region.as_str()).collect();
+            let total: Option<f64> = regions.into_iter().map(population).sum();
+            m.row.budget_per_capita = total.map(|n| m.row.total_budget / n);
+        }
+    }
+
+    /// Diffs Report 2 against the ranking file named by --compare-ranking. Runs before any
+    /// output is written, so the previous file may be the one this run is about to replace.
+    fn compare_ranking(&mut self, config: &Config) {
+        let Some(path) = &config.compare_ranking else { return };
+        if !config.wants(ReportKind::Contractors) {
+            return;
+        }
+        match read_previous_ranking(path) {
+            Ok(previous) => self.ranking_changes = compare_rankings(&previous, &self.report2),
+            Err(e) => println!("WARNING: Ranking comparison skipped; cannot read {}: {}", path, e),
+        }
+    }
+}
+
+/// Orchestrates the calculation of all reports, writes them to files, and returns the data
+/// along with the names of the files written.
+pub fn generate_reports(
+    projects: &[Project],
+    dataset_sha256: &str,
+    config: &Config,
+) -> Result<(Reports, Vec<String>), Box<dyn Error>> {
+    let reports = build_reports(projects, dataset_sha256, config);
+    let written_files = write_reports(&reports, Some(projects), config)?;
+    Ok((reports, written_files))
+}
+
+/// Calculates every selected report without writing anything.
+pub fn build_reports(projects: &[Project], dataset_sha256: &str, config: &Config) -> Reports {
+    let started = std::time::Instant::now();
+    // Unselected reports are left empty
+    let report2 = if config.wants(ReportKind::Contractors) {
+        calculate_financial_efficiencies(projects, config)
+    } else {
+        Vec::new()
+    };
+    let report1 = if config.wants(ReportKind::Regional) {
+        calculate_infrastructure_trends(projects, config)
+    } else {
+        Vec::new()
+    };
+    // Without Report 1 the summary's national score comes from lighter per-region figures
+    let mut summary = calculate_summary_json(projects, config.wants(ReportKind::Regional).then_some(&report1[..]), config);
+    summary.dataset_sha256 = dataset_sha256.to_string();
+    summary.delay_cap_days = config.delay_cap;
+    let wants = |kind| config.wants(kind);
+    let mut reports = Reports {
+        report1,
+        report2,
+        near_miss: if wants(ReportKind::Contractors) {
+            calculate_near_miss_contractors(projects, config)
+        } else {
+            Vec::new()
+        },
+        reach: if wants(ReportKind::Reach) { calculate_contractor_reach(projects) } else { Vec::new() },
+        report3: if wants(ReportKind::Annual) { calculate_performance_metrics(projects, config) } else { Vec::new() },
+        budget_tiers: if wants(ReportKind::BudgetTiers) { calculate_budget_tiers(projects, config) } else { Vec::new() },
+        region_yoy: if wants(ReportKind::RegionYoy) { calculate_region_budget_yoy(projects, &config.yoy_baselines) } else { Vec::new() },
+        churn: if wants(ReportKind::Churn) { calculate_contractor_churn(projects) } else { Vec::new() },
+        contractors_by_year: if wants(ReportKind::Churn) { count_contractors_by_year(projects) } else { BTreeMap::new() },
+        ranking_changes: Vec::new(),
+        report1_merged: if wants(ReportKind::Regional) { merge_small_regions(projects, config) } else { None },
+        report2_below_cost_floor: if wants(ReportKind::Contractors) { count_below_cost_floor(projects, config) } else { 0 },
+        population_matched: None,
+        summary,
+        non_finite_replaced: 0,
+    };
+    reports.join_population(config);
+    reports.compare_ranking(config);
+    reports.sanitize();
+    log::info!("Computed reports for {} projects in {:.2?}", projects.len(), started.elapsed());
+    reports
+}
+
+/// Writes the selected report files and returns their names. `projects` is needed only for
+/// the optional per-contractor project lists. report.txt is written only when every report
+/// is selected, so a partial run never replaces it with a partial copy.
+pub fn write_reports(
+    reports: &Reports,
+    projects: Option<&[Project]>,
+    config: &Config,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    // Write only the Top 15 for report 2
+    let report2_top15 = &reports.report2[..reports.report2.len().min(REPORT_2_TOP_N)];
+    let contractor_projects = match (projects, config.contractor_projects) {
+        (Some(projects), Some(limit)) if config.wants(ReportKind::Contractors) => {
+            Some(contractor_project_lists(&reports.report2, projects, limit))
+        }
+        _ => None,
+    };
+
+    let mut jobs: Vec<WriteJob> = Vec::new();
+    let (format, verify) = (config.table_format, config.verify_output);
+    if config.wants(ReportKind::Regional) {
+        push_table_jobs(&mut jobs, &config.output_name("report1_regional_summary"), &reports.report1, format, verify);
+        if config.geojson {
+            jobs.push((
+                config.output_name("report1_regions.geojson"),
+                Box::new(|path| write_json(&RegionFeatureCollection::new(&reports.report1), path)),
+            ));
+        }
+        if config.html {
+            jobs.push((
+                config.output_name("report1.html"),
+                Box::new(|path| {
+                    let context = format!("Filtered: 2021-2023 Projects; {}", config.missing_delay.label());
+                    write_html_table(&reports.report1, "Report 1: Regional Flood Mitigation Efficiency Summary", &context, config, path)
+                }),
+            ));
+        }
+    }
+    if config.wants(ReportKind::Contractors) {
+        push_table_jobs(&mut jobs, &config.output_name("report2_contractor_ranking"), report2_top15, format, verify);
+        if config.html {
+            jobs.push((
+                config.output_name("report2.html"),
+                Box::new(|path| {
+                    let context = format!(
+                        "Top 15 by {}, >={} Projects; {}",
+                        config.rank_by.label(),
+                        config.min_projects,
+                        config.missing_delay.label()
+                    );
+                    write_html_table(report2_top15, "Report 2: Top Contractors Performance Ranking", &context, config, path)
+                }),
+            ));
+        }
+        push_table_jobs(&mut jobs, &config.output_name("report2_near_miss_contractors"), &reports.near_miss, format, verify);
+        if !reports.ranking_changes.is_empty() {
+            push_table_jobs(&mut jobs, &config.output_name("report2_ranking_changes"), &reports.ranking_changes, format, verify);
+        }
+    }
+    if config.wants(ReportKind::Reach) {
+        push_table_jobs(&mut jobs, &config.output_name("contractor_reach"), &reports.reach, format, verify);
+    }
+    if config.wants(ReportKind::Annual) {
+        push_table_jobs(&mut jobs, &config.output_name("report3_annual_trends"), &reports.report3, format, verify);
+        if config.html {
+            jobs.push((
+                config.output_name("report3.html"),
+                Box::new(|path| {
+                    let context = format!("Grouped by FundingYear and TypeOfWork; {}", config.overrun_margin.label());
+                    write_html_table(&reports.report3, "Report 3: Annual Project Type Cost Overrun Trends", &context, config, path)
+                }),
+            ));
+        }
+    }
+    if config.wants(ReportKind::BudgetTiers) {
+        push_table_jobs(&mut jobs, &config.output_name("budget_tier_analysis"), &reports.budget_tiers, format, verify);
+    }
+    if config.wants(ReportKind::RegionYoy) {
+        push_table_jobs(&mut jobs, &config.output_name("region_budget_yoy"), &reports.region_yoy, format, verify);
+    }
+    if config.wants(ReportKind::Churn) {
+        push_table_jobs(&mut jobs, &config.output_name("contractor_churn"), &reports.churn, format, verify);
+    }
+    if config.wants(ReportKind::Summary) {
+        jobs.push((config.output_name("summary.json"), Box::new(|path| write_json(&reports.summary, path))));
+    }
+    jobs.push((
+        config.output_name("run_config.json"),
+        Box::new(|path| write_json(&RunConfig::new(config, &reports.summary.dataset_sha256), path)),
+    ));
+    if config.reports.is_empty() {
+        jobs.push((
+            config.output_name("report.txt"),
+            Box::new(|path| write_atomically(path, |out| Ok(write_text_report(out, reports, config, false)?))),
+        ));
+    }
+    if let Some(contractor_projects) = &contractor_projects {
+        jobs.push((
+            config.output_name("report2_contractor_projects.json"),
+            Box::new(|path| write_json(contractor_projects, path)),
+        ));
+    }
+    if config.append_history && config.wants(ReportKind::Summary) && !config.is_derived_run() {
+        jobs.push((
+            SUMMARY_HISTORY_FILE.to_string(),
+            Box::new(|path| append_summary_history(&reports.summary, path)),
+        ));
+    }
+    let written_files = jobs.iter().map(|(name, _)| name.clone()).collect();
+    let started = std::time::Instant::now();
+    write_outputs(jobs)?;
+    log::info!("Wrote report files in {:.2?}", started.elapsed());
+    Ok(written_files)
+}
+
+/// run_config.json: the settings behind a set of reports, serialized from the very `Config`
+/// that produced them, plus the fixed filters, so any run can be reproduced.
+#[derive(Debug, Serialize)]
+struct RunConfig<'a> {
+    program_version: &'static str,
+    dataset_sha256: &'a str,
+    funding_years: [i32; 2],
+    report2_top_n: usize,
+    settings: &'a Config,
+}
+
+impl<'a> RunConfig<'a> {
+    fn new(config: &'a Config, dataset_sha256: &'a str) -> Self {
+        RunConfig {
+            program_version: env!("CARGO_PKG_VERSION"),
+            dataset_sha256,
+            funding_years: [*FUNDING_YEARS.start(), *FUNDING_YEARS.end()],
+            report2_top_n: REPORT_2_TOP_N,
+            settings: config,
+        }
+    }
+}
+
+/// report1_regions.geojson: Report 1 rows as GeoJSON features for GIS tools. The dataset has
+/// no boundaries, so every geometry is null; join the features to a region layer on the
+/// `region` property.
+#[derive(Debug, Serialize)]
+struct RegionFeatureCollection<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    features: Vec<RegionFeature<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RegionFeature<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    geometry: Option<()>,
+    properties: &'a InfrastructureTrends,
+}
+
+impl<'a> RegionFeatureCollection<'a> {
+    fn new(report1: &'a [InfrastructureTrends]) -> Self {
+        RegionFeatureCollection {
+            kind: "FeatureCollection",
+            features: report1
+                .iter()
+                .map(|properties| RegionFeature { kind: "Feature", geometry: None, properties })
+                .collect(),
+        }
+    }
+}
+
+/// A ranked Report 2 row with the projects behind it, for audit exports.
+#[derive(Debug, Serialize)]
+struct ContractorProjects<'a> {
+    #[serde(flatten)]
+    metrics: &'a FinancialEfficiencies,
+    projects: Vec<&'a Project>,
+}
+
+/// Pairs the top `limit` ranked contractors with their projects.
+fn contractor_project_lists<'a>(
+    report2: &'a [FinancialEfficiencies],
+    projects: &'a [Project],
+    limit: usize,
+) -> Vec<ContractorProjects<'a>> {
+    let mut groups = group_by_contractor(projects);
+    report2
+        .iter()
+        .take(limit)
+        .map(|metrics| ContractorProjects {
+            metrics,
+            projects: groups.remove(&metrics.contractor).unwrap_or_default(),
+        })
+        .collect()
+}
+
+// Streaming aggregation ("large file" mode)
+
+/// Running totals for one group of projects, so reports can be built without keeping
+/// every `Project` in memory.
+#[derive(Debug, Default)]
+struct GroupAccumulator {
+    count: usize,
+    total_budget: Centavos,
+    total_cost: Centavos,
+    total_savings: Centavos,
+    overrun_count: usize,
+    /// Cost above budget summed over the overrun projects
+    overrun_amount: Centavos,
+    delay_sum: i64,
+    delay_count: usize,
+    /// Sum of positive delays only
+    late_days: i64,
+    high_delay_count: usize,
+    savings_rate_sum: f64,
+    /// Counts the non-zero-budget projects behind both rate sums
+    savings_rate_count: usize,
+    utilization_sum: f64,
+    /// Running median of cost_savings, fed only for groups that report one
+    savings_median: RunningMedian,
+    /// (cost_savings, approved_budget) pairs, stored only when the weighted median is wanted
+    savings: Vec<(f64, f64)>,
+    /// Delay (sum, count) per funding year, tracked only for contractor trends
+    year_delays: HashMap<i32, (i64, usize)>,
+    /// Funding years with projects, tracked only for contractor churn
+    years: BTreeSet<i32>,
+    /// Distinct regions, tracked only for contractors
+    regions: HashSet<String>,
+}
+
+impl GroupAccumulator {
+    fn add(&mut self, p: &Project, config: &Config) {
+        self.count += 1;
+        let (budget, cost) = (Centavos::from_pesos(p.approved_budget), Centavos::from_pesos(p.contract_cost));
+        self.total_budget += budget;
+        self.total_cost += cost;
+        self.total_savings += Centavos::from_pesos(p.cost_savings);
+        if config.overrun_margin.is_overrun(p) {
+            self.overrun_count += 1;
+            self.overrun_amount += cost - budget;
+        }
+        if p.approved_budget != 0.0 {
+            self.savings_rate_sum += p.cost_savings / p.approved_budget * 100.0;
+            self.utilization_sum += p.contract_cost / p.approved_budget * 100.0;
+            self.savings_rate_count += 1;
+        }
+        if let Some(delay) = policy_delay(p, config.missing_delay) {
+            self.delay_sum += delay;
+            self.delay_count += 1;
+            self.late_days += delay.max(0);
+            if delay > 30 {
+                self.high_delay_count += 1;
+            }
+        }
+    }
+
+    fn avg_delay(&self) -> Option<f64> {
+        (self.delay_count > 0).then(|| self.delay_sum as f64 / self.delay_count as f64)
+    }
+
+    fn overrun_rate(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.overrun_count as f64 / self.count as f64) * 100.0
+        }
+    }
+}
+
+/// Median of a stream of values, kept in two heaps: the lower half in a max-heap and the upper
+/// half in a min-heap, balanced so each push is O(log n) and the median is read off the tops.
+/// Matches `median` exactly, including the mean of the middle two for an even count.
+#[derive(Debug, Default)]
+struct RunningMedian {
+    lower: BinaryHeap<TotalF64>,
+    upper: BinaryHeap<Reverse<TotalF64>>,
+}
+
+/// f64 ordered with `total_cmp`, so it can live in a heap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TotalF64(f64);
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl RunningMedian {
+    fn push(&mut self, value: f64) {
+        match self.lower.peek() {
+            Some(top) if value > top.0 => self.upper.push(Reverse(TotalF64(value))),
+            _ => self.lower.push(TotalF64(value)),
+        }
+        // Keep the lower half equal to, or one larger than, the upper half
+        if self.lower.len() > self.upper.len() + 1
+            && let Some(moved) = self.lower.pop()
+        {
+            self.upper.push(Reverse(moved));
+        } else if self.upper.len() > self.lower.len()
+            && let Some(Reverse(moved)) = self.upper.pop()
+        {
+            self.lower.push(moved);
+        }
+    }
+
+    fn median(&self) -> f64 {
+        match (self.lower.peek(), self.upper.peek()) {
+            (None, _) => 0.0,
+            (Some(low), Some(Reverse(high))) if self.lower.len() == self.upper.len() => (low.0 + high.0) / 2.0,
+            (Some(low), _) => low.0,
+        }
+    }
+}
+
+/// Accumulators for every report grouping, fed one project at a time.
+#[derive(Debug)]
+struct StreamingAggregates {
+    regions: HashMap<(String, String), GroupAccumulator>,
+    contractors: HashMap<String, GroupAccumulator>,
+    year_types: HashMap<(i32, String), GroupAccumulator>,
+    tiers: Vec<GroupAccumulator>,
+    overall: GroupAccumulator,
+    region_names: HashSet<String>,
+    province_names: HashSet<String>,
+    budget_by_year: BTreeMap<i32, Centavos>,
+    /// Every approved budget, for the Gini coefficient
+    budgets: Vec<f64>,
+    region_year_budgets: BTreeMap<(String, i32), Centavos>,
+    islands: BTreeMap<String, GroupAccumulator>,
+    /// Context-year projects, kept out of every other total
+    baselines: YoyBaselines,
+}
+
+impl StreamingAggregates {
+    fn new() -> Self {
+        StreamingAggregates {
+            regions: HashMap::new(),
+            contractors: HashMap::new(),
+            year_types: HashMap::new(),
+            tiers: BUDGET_TIERS.iter().map(|_| GroupAccumulator::default()).collect(),
+            overall: GroupAccumulator::default(),
+            region_names: HashSet::new(),
+            province_names: HashSet::new(),
+            budget_by_year: BTreeMap::new(),
+            budgets: Vec::new(),
+            region_year_budgets: BTreeMap::new(),
+            islands: BTreeMap::new(),
+            baselines: YoyBaselines::default(),
+        }
+    }
+
+    fn add(&mut self, p: Project, config: &Config) {
+        if !FUNDING_YEARS.contains(&p.funding_year) {
+            self.baselines.add(&p);
+            return;
+        }
+        let region = self
+            .regions
+            .entry((p.region.clone(), p.main_island.clone()))
+            .or_default();
+        region.add(&p, config);
+        region.savings_median.push(p.cost_savings);
+        if config.weighted_median {
+            region.savings.push((p.cost_savings, p.approved_budget));
+        }
+
+        let contractor = self.contractors.entry(p.contractor.clone()).or_default();
+        contractor.add(&p, config);
+        if !contractor.regions.contains(&p.region) {
+            contractor.regions.insert(p.region.clone());
+        }
+        contractor.years.insert(p.funding_year);
+        if let Some(delay) = policy_delay(&p, config.missing_delay) {
+            let year = contractor.year_delays.entry(p.funding_year).or_default();
+            year.0 += delay;
+            year.1 += 1;
+        }
+
+        self.year_types
+            .entry((p.funding_year, p.type_of_work.clone()))
+            .or_default()
+            .add(&p, config);
+        self.tiers[budget_tier_index(p.approved_budget)].add(&p, config);
+        self.overall.add(&p, config);
+        self.islands.entry(p.main_island.clone()).or_default().add(&p, config);
+        *self.budget_by_year.entry(p.funding_year).or_default() += Centavos::from_pesos(p.approved_budget);
+        self.budgets.push(p.approved_budget);
+        *self
+            .region_year_budgets
+            .entry((p.region.clone(), p.funding_year))
+            .or_default() += Centavos::from_pesos(p.approved_budget);
+        if !p.province.is_empty() {
+            self.province_names.insert(p.province);
+        }
+        self.region_names.insert(p.region);
+    }
+
+    /// Turns the running totals into the same report rows the batch path produces.
+    fn finish(mut self, config: &Config) -> Reports {
+        let mut report1: Vec<InfrastructureTrends> = self
+            .regions
+            .into_iter()
+            .map(|((region, main_island), mut acc)| {
+                let median_savings = acc.savings_median.median();
+                let weighted_median_savings = config
+                    .weighted_median
+                    .then(|| weighted_median(&mut acc.savings));
+                let avg_delay = acc.avg_delay();
+                let high_delay_pct = if acc.delay_count == 0 {
+                    0.0
+                } else {
+                    (acc.high_delay_count as f64 / acc.delay_count as f64) * 100.0
+                };
+                InfrastructureTrends {
+                    region,
+                    main_island,
+                    total_budget: acc.total_budget.to_pesos(),
+                    budget_per_capita: None,
+                    median_savings,
+                    weighted_median_savings,
+                    avg_delay,
+                    high_delay_pct,
+                    overrun_rate: acc.overrun_rate(),
+                    efficiency_score: calculate_efficiency_score(median_savings, avg_delay.unwrap_or(0.0)),
+                    efficiency_raw: config.raw_scores.then(|| raw_efficiency_score(median_savings, avg_delay.unwrap_or(0.0))),
+                    low_sample: acc.count < config.min_region_sample,
+                }
+            })
+            .collect();
+        sort_report_1(&mut report1);
+
+        let grand_total_cost = self.overall.total_cost.to_pesos();
+        let lower = config.near_miss_min;
+        let upper = config.near_miss_upper();
+        let mut report2 = Vec::new();
+        let mut near_miss = Vec::new();
+        let mut reach = Vec::new();
+        let mut churn = Vec::new();
+        let mut contractors_by_year: BTreeMap<i32, usize> = BTreeMap::new();
+        let span = churn_span(&self.budget_by_year);
+        let mut below_cost_floor = 0;
+        let hhi = contractor_hhi(self.contractors.values().map(|acc| acc.total_cost.to_pesos()));
+        for (contractor, acc) in self.contractors {
+            reach.push(ContractorReach {
+                contractor: contractor.clone(),
+                region_count: acc.regions.len(),
+                total_projects: acc.count as i32,
+                total_cost: acc.total_cost.to_pesos(),
+            });
+            for year in &acc.years {
+                *contractors_by_year.entry(*year).or_default() += 1;
+            }
+            if let Some(span) = span {
+                churn.extend(churn_row(&contractor, &acc.years, span, acc.count, acc.total_cost.to_pesos()));
+            }
+            if (lower..=upper).contains(&acc.count) {
+                near_miss.push(NearMissContractor {
+                    contractor: contractor.clone(),
+                    num_projects: acc.count as i32,
+                    total_cost: acc.total_cost.to_pesos(),
+                });
+            }
+            if acc.count < config.min_projects {
+                continue;
+            }
+            if config.below_cost_floor(acc.total_cost.to_pesos()) {
+                below_cost_floor += 1;
+                continue;
+            }
+
+            let avg_delay = acc.avg_delay();
+            let market_share_pct = if grand_total_cost != 0.0 {
+                (acc.total_cost.to_pesos() / grand_total_cost) * 100.0
+            } else {
+                0.0
+            };
+            let raw_reliability = raw_reliability_index(avg_delay.unwrap_or(0.0), acc.total_savings.to_pesos(), acc.total_cost.to_pesos());
+            let reliability_index = raw_reliability.min(100.0);
+            let year_avg = |year: &i32| {
+                let (sum, count) = acc.year_delays[year];
+                sum as f64 / count as f64
+            };
+            let trend = match (acc.year_delays.keys().min(), acc.year_delays.keys().max()) {
+                (Some(first), Some(last)) if first != last => delay_trend_label(year_avg(first), year_avg(last)),
+                _ => "Insufficient".to_string(),
+            };
+
+            report2.push(FinancialEfficiencies {
+                rank: 0,
+                contractor,
+                total_cost: acc.total_cost.to_pesos(),
+                market_share_pct,
+                num_projects: acc.count as i32,
+                avg_project_cost: acc.total_cost.to_pesos() / acc.count as f64,
+                avg_delay,
+                total_savings: acc.total_savings.to_pesos(),
+                savings_rate_pct: calculate_savings_rate(acc.total_savings.to_pesos(), acc.total_cost.to_pesos()),
+                savings_per_delay_day: calculate_savings_per_delay_day(acc.total_savings.to_pesos(), acc.late_days),
+                reliability_index,
+                reliability_raw: config.raw_scores.then_some(raw_reliability),
+                recent_reliability_index: None,
+                risk_flag: risk_flag(reliability_index),
+                savings_anomaly_flag: savings_anomaly_flag(acc.total_savings.to_pesos(), acc.total_cost.to_pesos(), config),
+                trend,
+                avg_delay_vs_dataset: None,
+                savings_rate_vs_dataset: None,
+            });
+        }
+        if config.compare_to_average {
+            compare_to_dataset_average(
+                &mut report2,
+                self.overall.avg_delay(),
+                calculate_savings_rate(self.overall.total_savings.to_pesos(), grand_total_cost),
+            );
+        }
+        rank_report_2(&mut report2, config.rank_by, config.rank_style);
+        near_miss.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap_or(Ordering::Equal));
+        sort_contractor_reach(&mut reach);
+        sort_contractor_churn(&mut churn);
+
+        let mut report3: Vec<PerformanceMetrics> = self
+            .year_types
+            .into_iter()
+            .map(|((funding_year, type_of_work), acc)| PerformanceMetrics {
+                funding_year,
+                type_of_work,
+                total_projects: acc.count as i32,
+                total_savings: acc.total_savings.to_pesos(),
+                avg_savings: acc.total_savings.to_pesos() / acc.count as f64,
+                overrun_rate: acc.overrun_rate(),
+                avg_utilization_pct: if acc.savings_rate_count == 0 {
+                    0.0
+                } else {
+                    acc.utilization_sum / acc.savings_rate_count as f64
+                },
+                yoy_change: 0.0,
+                sample_adequacy: sample_adequacy(acc.count, config),
+            })
+            .collect();
+        finish_report_3(&mut report3, &self.baselines);
+
+        let budget_tiers = BUDGET_TIERS
+            .iter()
+            .zip(self.tiers)
+            .map(|((label, _), acc)| BudgetTierAnalysis {
+                budget_tier: label.to_string(),
+                project_count: acc.count as i32,
+                total_budget: acc.total_budget.to_pesos(),
+                avg_savings_rate: if acc.savings_rate_count == 0 {
+                    0.0
+                } else {
+                    acc.savings_rate_sum / acc.savings_rate_count as f64
+                },
+                overrun_rate: acc.overrun_rate(),
+            })
+            .collect();
+
+        let summary = SummaryJson {
+            total_projects_analyzed: self.overall.count,
+            total_budget_analyzed: self.overall.total_budget.to_pesos(),
+            global_avg_delay: self.overall.avg_delay(),
+            national_efficiency_score: national_efficiency_score(&report1),
+            total_contractors: report2.len(),
+            total_provinces: province_count(self.province_names.len(), self.region_names.len()),
+            budget_by_year: self.budget_by_year.into_iter().map(|(year, budget)| (year, budget.to_pesos())).collect(),
+            total_overrun_amount: self.overall.overrun_amount.to_pesos(),
+            overrun_project_count: self.overall.overrun_count,
+            contractor_hhi: hhi,
+            budget_gini: gini(&mut self.budgets),
+            island_breakdown: self
+                .islands
+                .into_iter()
+                .map(|(island, acc)| {
+                    let summary = IslandSummary {
+                        project_count: acc.count,
+                        total_budget: acc.total_budget.to_pesos(),
+                        total_savings: acc.total_savings.to_pesos(),
+                        avg_delay: acc.avg_delay(),
+                    };
+                    (island, summary)
+                })
+                .collect(),
+            delay_cap_days: None,
+            dataset_sha256: String::new(),
+        };
+
+        let mut reports = Reports {
+            report1,
+            report2,
+            near_miss,
+            reach,
+            report3,
+            budget_tiers,
+            region_yoy: finish_region_budget_yoy(self.region_year_budgets, &self.baselines),
+            churn,
+            contractors_by_year,
+            ranking_changes: Vec::new(),
+            report1_merged: None,
+            report2_below_cost_floor: below_cost_floor,
+            population_matched: None,
+            summary,
+            non_finite_replaced: 0,
+        };
+        reports.join_population(config);
+        reports.compare_ranking(config);
+        reports.sanitize();
+        reports
+    }
+}
+
+/// Large-file mode: aggregates the reports while reading, applying any --work-type filter
+/// on the fly, so only per-group totals are held in memory.
+pub fn stream_reports(paths: &[String], config: &Config) -> Result<(LoadSummary, Reports), Box<dyn Error>> {
+    let work_type_terms: Vec<String> = config.work_types.iter().map(|t| t.to_lowercase()).collect();
+    let mut aggregates = StreamingAggregates::new();
+    let summary = load_data_with(paths, config, |project| {
+        if work_type_terms.is_empty() || matches_work_type(&project, &work_type_terms) {
+            aggregates.add(project, config);
+        }
+    })?;
+    let mut reports = aggregates.finish(config);
+    reports.summary.dataset_sha256.clone_from(&summary.dataset_sha256);
+    Ok((summary, reports))
+}
+
+/// True when a non-zero amount is an exact multiple of `divisor` (to the centavo).
+fn is_round_amount(value: f64, divisor: f64) -> bool {
+    let remainder = (value % divisor).abs();
+    value != 0.0 && (remainder < 0.005 || divisor - remainder < 0.005)
+}
+
+/// Data-quality heuristic: projects whose budget or cost is a suspiciously round figure,
+/// which often means a placeholder estimate.
+pub fn find_round_number_projects(projects: &[Project], divisor: f64) -> Vec<RoundNumberFlag> {
+    projects
+        .iter()
+        .filter_map(|p| {
+            let mut round_fields = Vec::new();
+            if is_round_amount(p.approved_budget, divisor) {
+                round_fields.push("approved_budget");
+            }
+            if is_round_amount(p.contract_cost, divisor) {
+                round_fields.push("contract_cost");
+            }
+            if round_fields.is_empty() {
+                return None;
+            }
+            Some(RoundNumberFlag {
+                project_id: p.project_id.clone(),
+                contractor: p.contractor.clone(),
+                region: p.region.clone(),
+                funding_year: p.funding_year,
+                type_of_work: p.type_of_work.clone(),
+                approved_budget: p.approved_budget,
+                contract_cost: p.contract_cost,
+                round_fields: round_fields.join(";"),
+            })
+        })
+        .collect()
+}
+
+/// Savings (in pesos) this close to zero count as a bid exactly at budget.
+pub const AT_BUDGET_TOLERANCE: f64 = 0.01;
+
+/// Integrity check: contractors with at least `min_projects` projects whose share of projects
+/// with zero savings (cost equal to budget) exceeds `fraction`, highest share first.
+pub fn find_at_budget_contractors(projects: &[Project], min_projects: usize, fraction: f64) -> Vec<AtBudgetContractor> {
+    let mut flagged: Vec<AtBudgetContractor> = group_by_contractor(projects)
+        .into_iter()
+        .filter(|(_, group)| group.len() >= min_projects.max(1))
+        .filter_map(|(contractor, group)| {
+            let at_budget: Vec<&Project> = group
+                .iter()
+                .copied()
+                .filter(|p| p.cost_savings.abs() <= AT_BUDGET_TOLERANCE)
+                .collect();
+            let at_budget_pct = at_budget.len() as f64 / group.len() as f64 * 100.0;
+            (at_budget_pct > fraction * 100.0).then(|| AtBudgetContractor {
+                contractor,
+                num_projects: group.len(),
+                at_budget_projects: at_budget.len(),
+                at_budget_pct,
+                total_cost: sum_money(group.iter().map(|p| p.contract_cost)),
+                at_budget_project_ids: at_budget.iter().map(|p| p.project_id.as_str()).collect::<Vec<_>>().join(";"),
+            })
+        })
+        .collect();
+    flagged.sort_by(|a, b| {
+        b.at_budget_pct
+            .total_cmp(&a.at_budget_pct)
+            .then(b.num_projects.cmp(&a.num_projects))
+            .then_with(|| a.contractor.cmp(&b.contractor))
+    });
+    flagged
+}
+
+/// Investigative heuristic: within each (contractor, region, type of work), projects sorted by
+/// budget are clustered while each budget stays within `tolerance` (a fraction) above the
+/// cluster's smallest. Clusters of two or more are returned, largest first.
+pub fn find_repeat_contracts(projects: &[Project], tolerance: f64) -> Vec<RepeatContractCluster> {
+    let mut groups: HashMap<(&str, &str, &str), Vec<&Project>> = HashMap::new();
+    for p in projects {
+        groups
+            .entry((p.contractor.as_str(), p.region.as_str(), p.type_of_work.as_str()))
+            .or_default()
+            .push(p);
+    }
+
+    let mut clusters = Vec::new();
+    for ((contractor, region, type_of_work), mut group) in groups {
+        group.sort_by(|a, b| a.approved_budget.total_cmp(&b.approved_budget));
+        let mut start = 0;
+        while start < group.len() {
+            let limit = group[start].approved_budget * (1.0 + tolerance);
+            let end = start + group[start..].iter().take_while(|p| p.approved_budget <= limit).count();
+            let cluster = &group[start..end];
+            if cluster.len() >= 2 {
+                let years: BTreeSet<i32> = cluster.iter().map(|p| p.funding_year).collect();
+                clusters.push(RepeatContractCluster {
+                    contractor: contractor.to_string(),
+                    region: region.to_string(),
+                    type_of_work: type_of_work.to_string(),
+                    num_projects: cluster.len(),
+                    min_budget: cluster[0].approved_budget,
+                    max_budget: cluster[cluster.len() - 1].approved_budget,
+                    total_budget: sum_money(cluster.iter().map(|p| p.approved_budget)),
+                    funding_years: years.iter().map(i32::to_string).collect::<Vec<_>>().join(";"),
+                    project_ids: cluster.iter().map(|p| p.project_id.as_str()).collect::<Vec<_>>().join(";"),
+                });
+            }
+            start = end;
+        }
+    }
+    clusters.sort_by(|a, b| {
+        b.num_projects
+            .cmp(&a.num_projects)
+            .then(b.total_budget.total_cmp(&a.total_budget))
+            .then_with(|| a.contractor.cmp(&b.contractor))
+    });
+    clusters
+}
+
+/// Data-quality heuristic: pairs of distinct contractor names whose similarity is at least
+/// `threshold`, most similar first. Nothing is merged; the list is for manual review.
+pub fn find_contractor_duplicates(projects: &[Project], threshold: f64) -> Vec<ContractorDuplicate> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for p in projects {
+        *counts.entry(p.contractor.as_str()).or_default() += 1;
+    }
+    let names: Vec<(&str, NameKey, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name, NameKey::new(name), count))
+        .collect();
+
+    let mut dupes = Vec::new();
+    for (i, (name_a, key_a, count_a)) in names.iter().enumerate() {
+        for (name_b, key_b, count_b) in &names[i + 1..] {
+            let similarity = key_a.similarity(key_b, threshold);
+            if similarity >= threshold {
+                dupes.push(ContractorDuplicate {
+                    contractor_a: name_a.to_string(),
+                    contractor_b: name_b.to_string(),
+                    similarity,
+                    projects_a: *count_a,
+                    projects_b: *count_b,
+                });
+            }
+        }
+    }
+    dupes.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(Ordering::Equal));
+    dupes
+}
+
+/// Common abbreviations in contractor names, expanded before comparing words.
+const CONTRACTOR_ABBREVIATIONS: [(&str, &str); 12] = [
+    ("const", "construction"),
+    ("constn", "construction"),
+    ("constr", "construction"),
+    ("corp", "corporation"),
+    ("inc", "incorporated"),
+    ("co", "company"),
+    ("dev", "development"),
+    ("devt", "development"),
+    ("gen", "general"),
+    ("svcs", "services"),
+    ("ent", "enterprises"),
+    ("bldrs", "builders"),
+];
+
+/// A contractor name prepared for comparison: lowercased, initials joined ("A.B." -> "ab"),
+/// other punctuation dropped and abbreviations expanded.
+struct NameKey {
+    chars: Vec<char>,
+    words: Vec<String>,
+    /// Letter and digit counts, with everything else in the last slot
+    char_counts: [i32; 37],
+}
+
+impl NameKey {
+    fn new(name: &str) -> Self {
+        let cleaned: String = name
+            .chars()
+            .filter(|c| !matches!(c, '.' | '\''))
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+            .collect();
+        let words: Vec<String> = cleaned
+            .split_whitespace()
+            .map(|word| {
+                CONTRACTOR_ABBREVIATIONS
+                    .iter()
+                    .find(|(short, _)| *short == word)
+                    .map_or(word, |(_, long)| long)
+                    .to_string()
+            })
+            .collect();
+        let chars: Vec<char> = words.join(" ").chars().collect();
+        let mut char_counts = [0; 37];
+        for c in &chars {
+            let slot = match c {
+                'a'..='z' => *c as usize - 'a' as usize,
+                '0'..='9' => 26 + (*c as usize - '0' as usize),
+                _ => 36,
+            };
+            char_counts[slot] += 1;
+        }
+        NameKey { chars, words, char_counts }
+    }
+
+    /// Similarity from 0 to 1: the better of the edit-distance ratio (catches typos) and the
+    /// word overlap (catches reordered or extra words). The edit distance is skipped when a
+    /// cheap bound shows it cannot reach `threshold`.
+    fn similarity(&self, other: &NameKey, threshold: f64) -> f64 {
+        let max_len = self.chars.len().max(other.chars.len());
+        if max_len == 0 {
+            return 1.0;
+        }
+
+        let mut unmatched: Vec<&String> = other.words.iter().collect();
+        let shared = self
+            .words
+            .iter()
+            .filter(|w| match unmatched.iter().position(|u| u == w) {
+                Some(index) => {
+                    unmatched.swap_remove(index);
+                    true
+                }
+                None => false,
+            })
+            .count();
+        let word_overlap = shared as f64 / self.words.len().max(other.words.len()) as f64;
+
+        // Each character one name has in excess of the other needs at least one edit
+        let (mut surplus, mut deficit) = (0, 0);
+        for (a, b) in self.char_counts.iter().zip(&other.char_counts) {
+            if a > b {
+                surplus += a - b;
+            } else {
+                deficit += b - a;
+            }
+        }
+        let best_edit_ratio = 1.0 - surplus.max(deficit) as f64 / max_len as f64;
+        if best_edit_ratio < threshold || best_edit_ratio <= word_overlap {
+            return word_overlap;
+        }
+
+        let edit_ratio = 1.0 - levenshtein(&self.chars, &other.chars) as f64 / max_len as f64;
+        edit_ratio.max(word_overlap)
+    }
+}
+
+/// Character-level Levenshtein distance.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Projects that have started but have no completion date, with days elapsed up to `today`.
+/// Kept apart from the completed-project delay metrics; longest-running first.
+pub fn calculate_ongoing_projects(projects: &[Project], today: NaiveDate, threshold_days: i64) -> Vec<OngoingProject> {
+    let mut ongoing: Vec<OngoingProject> = projects
+        .iter()
+        .filter(|p| p.completion_delay_days.is_none())
+        .filter_map(|p| {
+            let start_date = p.start_date?;
+            let days_elapsed = (today - start_date).num_days();
+            Some(OngoingProject {
+                project_id: p.project_id.clone(),
+                contractor: p.contractor.clone(),
+                region: p.region.clone(),
+                funding_year: p.funding_year,
+                type_of_work: p.type_of_work.clone(),
+                approved_budget: p.approved_budget,
+                start_date,
+                days_elapsed,
+                overdue: days_elapsed > threshold_days,
+            })
+        })
+        .collect();
+
+    ongoing.sort_by_key(|o| std::cmp::Reverse(o.days_elapsed));
+    ongoing
+}
+
+/// Spreadsheet-style label for a zero-based index: A..Z, AA, AB, ...
+fn pseudonym_label(mut index: usize) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap_or_default()
+}
+
+/// Replaces every contractor name with a stable pseudonym ("Contractor A", ...).
+/// Names are ordered by Report 2 rank, then the unranked contractors by total cost and name,
+/// so the same data always yields the same pseudonyms. Returns the pseudonym key.
+pub fn anonymize_contractors(projects: &mut [Project], config: &Config) -> Vec<ContractorKey> {
+    let ranked: Vec<String> = calculate_financial_efficiencies(projects, config)
+        .into_iter()
+        .map(|r| r.contractor)
+        .collect();
+    let ranked_set: HashSet<&String> = ranked.iter().collect();
+
+    let mut unranked: Vec<(String, f64)> = group_by_contractor(projects)
+        .into_iter()
+        .filter(|(name, _)| !ranked_set.contains(name))
+        .map(|(name, group)| (name, sum_money(group.iter().map(|p| p.contract_cost))))
+        .collect();
+    unranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let key: Vec<ContractorKey> = ranked
+        .iter()
+        .cloned()
+        .chain(unranked.into_iter().map(|(name, _)| name))
+        .enumerate()
+        .map(|(i, contractor)| ContractorKey {
+            pseudonym: format!("Contractor {}", pseudonym_label(i)),
+            contractor,
+        })
+        .collect();
+
+    let lookup: HashMap<&str, &str> = key
+        .iter()
+        .map(|k| (k.contractor.as_str(), k.pseudonym.as_str()))
+        .collect();
+    for p in projects.iter_mut() {
+        if let Some(pseudonym) = lookup.get(p.contractor.as_str()) {
+            p.contractor = pseudonym.to_string();
+        }
+    }
+    key
+}
+
+// Library API: one entry point per report, for callers that bring their own projects
+
+/// Report 1: regional efficiency summary, sorted by efficiency score.
+pub fn build_report1(projects: &[Project], config: &Config) -> Vec<InfrastructureTrends> {
+    calculate_infrastructure_trends(projects, config)
+}
+
+/// Report 2: every contractor with at least `config.min_projects` projects, ranked.
+pub fn build_report2(projects: &[Project], config: &Config) -> Vec<FinancialEfficiencies> {
+    calculate_financial_efficiencies(projects, config)
+}
+
+/// Report 3: totals per funding year and type of work, with year-over-year change.
+pub fn build_report3(projects: &[Project], config: &Config) -> Vec<PerformanceMetrics> {
+    calculate_performance_metrics(projects, config)
+}
+
+/// The figures written to summary.json, except the dataset hash, which only a load knows.
+pub fn generate_summary(projects: &[Project], config: &Config) -> SummaryJson {
+    calculate_summary_json(projects, None, config)
+}
+
+/// Report 1: Calculates Infrastructure Trends
+fn calculate_infrastructure_trends(projects: &[Project], config: &Config) -> Vec<InfrastructureTrends> {
+    let mut region_map: HashMap<(String, String), Vec<&Project>> = HashMap::new();
+    for p in projects {
+        region_map
+            .entry((p.region.clone(), p.main_island.clone()))
+            .or_default()
+            .push(p);
+    }
+
+    let mut report1: Vec<InfrastructureTrends> = region_map
+        .into_iter()
+        .map(|((region, main_island), group)| infrastructure_row(region, main_island, &group, config))
+        .collect();
+
+    sort_report_1(&mut report1);
+    report1
+}
+
+/// One Report 1 row computed over `group`.
+fn infrastructure_row(region: String, main_island: String, group: &[&Project], config: &Config) -> InfrastructureTrends {
+    let total_budget = sum_money(group.iter().map(|p| p.approved_budget));
+    let median_savings = calculate_median_savings(group);
+    let weighted_median_savings = config
+        .weighted_median
+        .then(|| calculate_weighted_median_savings(group));
+
+    let delays = collect_delays(group.iter().copied(), config.missing_delay);
+    let avg_delay = capped_average(&delays, config.delay_cap);
+    let high_delay_pct = if !delays.is_empty() {
+        let high_count = delays.iter().filter(|&&d| d > 30).count();
+        (high_count as f64 / delays.len() as f64) * 100.0
+    } else {
+        0.0
+    };
+    let overrun_count = group
+        .iter()
+        .filter(|p| config.overrun_margin.is_overrun(p))
+        .count();
+    let overrun_rate = (overrun_count as f64 / group.len() as f64) * 100.0;
+    let efficiency_score = calculate_efficiency_score(median_savings, avg_delay.unwrap_or(0.0));
+
+    InfrastructureTrends {
+        region,
+        main_island,
+        total_budget,
+        budget_per_capita: None,
+        median_savings,
+        weighted_median_savings,
+        avg_delay,
+        high_delay_pct,
+        overrun_rate,
+        efficiency_score,
+        efficiency_raw: config.raw_scores.then(|| raw_efficiency_score(median_savings, avg_delay.unwrap_or(0.0))),
+        low_sample: group.len() < config.min_region_sample,
+    }
+}
+
+/// Pools the projects of every (region, main island) with fewer than
+/// `config.merge_regions_below` projects into one "Other" row. None when the option is
+/// off or fewer than two regions qualify, since merging one region changes nothing.
+fn merge_small_regions(projects: &[Project], config: &Config) -> Option<MergedRegions> {
+    let min = config.merge_regions_below?;
+    let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+    for p in projects {
+        *counts.entry((p.region.as_str(), p.main_island.as_str())).or_default() += 1;
+    }
+    let group: Vec<&Project> = projects
+        .iter()
+        .filter(|p| counts[&(p.region.as_str(), p.main_island.as_str())] < min)
+        .collect();
+    let merged: HashSet<(String, String)> = group
+        .iter()
+        .map(|p| (p.region.clone(), p.main_island.clone()))
+        .collect();
+    if merged.len() < 2 {
+        return None;
+    }
+    let row = infrastructure_row("Other".to_string(), "-".to_string(), &group, config);
+    Some(MergedRegions { row, merged })
+}
+
+fn calculate_efficiency_score(median_savings: f64, avg_delay: f64) -> f64 {
+    raw_efficiency_score(median_savings, avg_delay).clamp(0.0, 100.0) // per REQ-0006
+}
+
+/// The efficiency score before clamping, shown by --raw-scores.
+fn raw_efficiency_score(median_savings: f64, avg_delay: f64) -> f64 {
+    if avg_delay.abs() > 0.001 {
+        (median_savings / avg_delay) * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn sort_report_1(report1: &mut [InfrastructureTrends]) {
+    report1.sort_by(|a, b| {
+        b.efficiency_score
+            .partial_cmp(&a.efficiency_score)
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+fn group_by_contractor(projects: &[Project]) -> HashMap<String, Vec<&Project>> {
+    let mut contractor_map: HashMap<String, Vec<&Project>> = HashMap::new();
+    for p in projects {
+        contractor_map
+            .entry(p.contractor.clone())
+            .or_default()
+            .push(p);
+    }
+    contractor_map
+}
+
+fn calculate_financial_efficiencies(projects: &[Project], config: &Config) -> Vec<FinancialEfficiencies> {
+    let contractor_map = group_by_contractor(projects);
+
+    // Market share is measured against every contractor, not just the ranked ones
+    let grand_total_cost = sum_money(contractor_map.values().flat_map(|group| group.iter().map(|p| p.contract_cost)));
+
+    let mut report2 = Vec::new();
+    for (contractor, group) in contractor_map {
+        let num_projects = group.len() as i32;
+
+        if (num_projects as usize) < config.min_projects {
+            continue;
+        }
+
+        let total_cost = sum_money(group.iter().map(|p| p.contract_cost));
+        if config.below_cost_floor(total_cost) {
+            continue;
+        }
+        let total_savings = sum_money(group.iter().map(|p| p.cost_savings));
+        let avg_delay = capped_average(&collect_delays(group.iter().copied(), config.missing_delay), config.delay_cap);
+        let late_days: i64 = collect_delays(group.iter().copied(), config.missing_delay)
+            .into_iter()
+            .map(|d| d.max(0))
+            .sum();
+        let market_share_pct = if grand_total_cost != 0.0 {
+            (total_cost / grand_total_cost) * 100.0
+        } else {
+            0.0
+        };
+        let raw_reliability = raw_reliability_index(avg_delay.unwrap_or(0.0), total_savings, total_cost);
+        let reliability_index = raw_reliability.min(100.0);
+
+        report2.push(FinancialEfficiencies {
+            rank: 0,
+            contractor,
+            total_cost,
+            market_share_pct,
+            num_projects,
+            avg_project_cost: total_cost / num_projects as f64,
+            avg_delay,
+            total_savings,
+            savings_rate_pct: calculate_savings_rate(total_savings, total_cost),
+            savings_per_delay_day: calculate_savings_per_delay_day(total_savings, late_days),
+            reliability_index,
+            reliability_raw: config.raw_scores.then_some(raw_reliability),
+            recent_reliability_index: config
+                .recency_decay
+                .map(|decay| recency_weighted_reliability(&group, config, decay)),
+            risk_flag: risk_flag(reliability_index),
+            savings_anomaly_flag: savings_anomaly_flag(total_savings, total_cost, config),
+            trend: calculate_delay_trend(&group, config.missing_delay),
+            avg_delay_vs_dataset: None,
+            savings_rate_vs_dataset: None,
+        });
+    }
+
+    if config.compare_to_average {
+        let total_savings = sum_money(projects.iter().map(|p| p.cost_savings));
+        compare_to_dataset_average(
+            &mut report2,
+            capped_average(&collect_delays(projects, config.missing_delay), config.delay_cap),
+            calculate_savings_rate(total_savings, grand_total_cost),
+        );
+    }
+    rank_report_2(&mut report2, config.rank_by, config.rank_style);
+    report2
+}
+
+/// The number of Report 2 rows, counted without building them: contractors with at least
+/// `config.min_projects` projects and a total cost not below the floor.
+fn count_ranked_contractors(projects: &[Project], config: &Config) -> usize {
+    group_by_contractor(projects)
+        .values()
+        .filter(|group| group.len() >= config.min_projects)
+        .filter(|group| !config.below_cost_floor(sum_money(group.iter().map(|p| p.contract_cost))))
+        .count()
+}
+
+/// Contractors that meet `config.min_projects` but are left out of Report 2 by the cost floor.
+fn count_below_cost_floor(projects: &[Project], config: &Config) -> usize {
+    if config.min_total_cost.is_none_or(|floor| floor <= 0.0) {
+        return 0;
+    }
+    group_by_contractor(projects)
+        .values()
+        .filter(|group| group.len() >= config.min_projects)
+        .filter(|group| config.below_cost_floor(sum_money(group.iter().map(|p| p.contract_cost))))
+        .count()
+}
+
+fn calculate_reliability_index(avg_delay: f64, total_savings: f64, total_cost: f64) -> f64 {
+    raw_reliability_index(avg_delay, total_savings, total_cost).min(100.0) // per REQ-0007
+}
+
+/// The reliability index before capping at 100, shown by --raw-scores.
+fn raw_reliability_index(avg_delay: f64, total_savings: f64, total_cost: f64) -> f64 {
+    let total_cost_safe = if total_cost == 0.0 { 1.0 } else { total_cost };
+    let delay_factor = 1.0 - (avg_delay / 90.0);
+    let savings_factor = total_savings / total_cost_safe;
+    delay_factor * savings_factor * 100.0
+}
+
+/// The reliability index computed with each project weighted by `decay^(2023 - funding year)`,
+/// so 2023 projects count fully, 2022 ones by `decay` and 2021 ones by `decay²`:
+///
+/// - average delay = Σ w·delay / Σ w, over the projects that have a delay under the policy
+///   (capped like the unweighted average)
+/// - savings factor = Σ w·savings / Σ w·cost
+///
+/// These replace the plain average and totals in the usual formula, and a decay of 1 gives
+/// back the unweighted index.
+fn recency_weighted_reliability(group: &[&Project], config: &Config, decay: f64) -> f64 {
+    let weight = |p: &Project| decay.powi(FUNDING_YEARS.end() - p.funding_year);
+    let cap = config.delay_cap.unwrap_or(i64::MAX);
+    let (mut delay_sum, mut delay_weight) = (0.0, 0.0);
+    let (mut savings, mut cost) = (0.0, 0.0);
+    for p in group {
+        let w = weight(p);
+        if let Some(delay) = policy_delay(p, config.missing_delay) {
+            delay_sum += w * delay.min(cap) as f64;
+            delay_weight += w;
+        }
+        savings += w * p.cost_savings;
+        cost += w * p.contract_cost;
+    }
+    let avg_delay = if delay_weight > 0.0 { delay_sum / delay_weight } else { 0.0 };
+    calculate_reliability_index(avg_delay, savings, cost)
+}
+
+/// Fills in each contractor's difference from the dataset-wide average delay and savings rate.
+fn compare_to_dataset_average(report2: &mut [FinancialEfficiencies], avg_delay: Option<f64>, savings_rate: f64) {
+    for row in report2 {
+        row.avg_delay_vs_dataset = row.avg_delay.zip(avg_delay).map(|(row_delay, avg_delay)| row_delay - avg_delay);
+        row.savings_rate_vs_dataset = Some(row.savings_rate_pct - savings_rate);
+    }
+}
+
+/// Savings as a percentage of contract cost, so contractors of different sizes compare fairly.
+fn calculate_savings_rate(total_savings: f64, total_cost: f64) -> f64 {
+    if total_cost == 0.0 {
+        0.0
+    } else {
+        total_savings / total_cost * 100.0
+    }
+}
+
+/// Savings earned per day of delay, summing only positive (late) delays. With no late days
+/// there is nothing to divide by, so the total savings is reported as-is.
+fn calculate_savings_per_delay_day(total_savings: f64, late_days: i64) -> f64 {
+    if late_days > 0 {
+        total_savings / late_days as f64
+    } else {
+        total_savings
+    }
+}
+
+fn risk_flag(reliability_index: f64) -> String {
+    if reliability_index < 50.0 {
+        "High Risk".to_string()
+    } else {
+        "Low Risk".to_string()
+    } // per REQ-0007
+}
+
+/// Flags savings that are implausibly high for the approved budget (a sign of padding) or
+/// low, including overruns. Independent of the reliability-based risk flag.
+fn savings_anomaly_flag(total_savings: f64, total_cost: f64, config: &Config) -> String {
+    // Savings are budget minus cost, so the budget is their sum
+    let total_budget = total_cost + total_savings;
+    let pct_of_budget = if total_budget == 0.0 { 0.0 } else { total_savings / total_budget * 100.0 };
+    if pct_of_budget > config.savings_flag_high {
+        "High Savings".to_string()
+    } else if pct_of_budget < config.savings_flag_low {
+        "Low Savings".to_string()
+    } else {
+        "Normal".to_string()
+    }
+}
+
+fn rank_report_2(report2: &mut [FinancialEfficiencies], mode: RankMode, style: RankStyle) {
+    // Rank by total ContractCost (descending) per REQ-0007 unless another key is chosen
+    let key = |r: &FinancialEfficiencies| match mode {
+        RankMode::Cost => r.total_cost,
+        RankMode::Reliability => r.reliability_index,
+        RankMode::Savings => r.total_savings,
+    };
+    report2.sort_by(|a, b| key(b).partial_cmp(&key(a)).unwrap_or(Ordering::Equal));
+
+    let mut rank = 0;
+    let mut previous_key = None;
+    for (i, row) in report2.iter_mut().enumerate() {
+        let tied = previous_key == Some(key(row));
+        rank = match style {
+            RankStyle::Ordinal => i + 1,
+            RankStyle::Competition if tied => rank,
+            RankStyle::Competition => i + 1,
+            RankStyle::Dense if tied => rank,
+            RankStyle::Dense => rank + 1,
+        };
+        row.rank = rank as i32;
+        previous_key = Some(key(row));
+    }
+}
+
+/// Reads a region,population CSV into a map keyed by lower-cased region name, skipping
+/// non-positive populations.
+fn read_populations(path: &str) -> Result<HashMap<String, f64>, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path)?;
+    let mut populations = HashMap::new();
+    for row in reader.deserialize() {
+        let row: RegionPopulation = row?;
+        if row.population > 0.0 {
+            populations.insert(row.region.to_lowercase(), row.population);
+        }
+    }
+    Ok(populations)
+}
+
+/// Reads a previous run's Report 2 table; a `.tsv` extension selects tab-separated input.
+fn read_previous_ranking(path: &str) -> Result<Vec<PreviousRanking>, Box<dyn Error>> {
+    let delimiter = if path.to_lowercase().ends_with(".tsv") { b'\t' } else { b',' };
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_path(path)?;
+    let rows = reader.deserialize().collect::<Result<Vec<PreviousRanking>, _>>()?;
+    Ok(rows)
+}
+
+/// Compares the new top 15 with a previous top 15: rows for every current entry in rank order,
+/// then the contractors that dropped out, by their previous rank.
+fn compare_rankings(previous: &[PreviousRanking], report2: &[FinancialEfficiencies]) -> Vec<RankingChange> {
+    let previous_by_name: HashMap<&str, &PreviousRanking> =
+        previous.iter().map(|p| (p.contractor.as_str(), p)).collect();
+    let current_by_name: HashMap<&str, &FinancialEfficiencies> =
+        report2.iter().map(|r| (r.contractor.as_str(), r)).collect();
+    let top15: Vec<_> = report2.iter().take(REPORT_2_TOP_N).collect();
+    let in_top15: HashSet<&str> = top15.iter().map(|r| r.contractor.as_str()).collect();
+
+    let change = |contractor: &str, old: Option<&PreviousRanking>, new: Option<&FinancialEfficiencies>| {
+        let rank_delta = old.zip(new).map(|(o, n)| o.rank - n.rank);
+        let status = match (old, new, rank_delta) {
+            (None, _, _) => "New",
+            (Some(_), _, _) if !in_top15.contains(contractor) => "Dropped",
+            (_, _, Some(delta)) if delta > 0 => "Up",
+            (_, _, Some(delta)) if delta < 0 => "Down",
+            _ => "Unchanged",
+        };
+        // The previous values were rounded on output; round the difference the same way so an
+        // unchanged metric reads as 0 rather than as rounding noise
+        let delta = |f: fn(&PreviousRanking, &FinancialEfficiencies) -> f64, column_default| {
+            old.zip(new).map(|(o, n)| round_to(f(o, n), decimals(column_default)) + 0.0)
+        };
+        RankingChange {
+            contractor: contractor.to_string(),
+            status: status.to_string(),
+            previous_rank: old.map(|o| o.rank),
+            current_rank: new.map(|n| n.rank),
+            rank_delta,
+            total_cost_delta: delta(|o, n| n.total_cost - o.total_cost, MONEY_DECIMALS),
+            savings_rate_delta: delta(|o, n| n.savings_rate_pct - o.savings_rate_pct, PERCENT_DECIMALS),
+            avg_delay_delta: old
+                .zip(new)
+                .and_then(|(o, n)| Some(round_to(n.avg_delay? - o.avg_delay?, decimals(DAYS_DECIMALS)) + 0.0)),
+            reliability_delta: delta(|o, n| n.reliability_index - o.reliability_index, SCORE_DECIMALS),
+        }
+    };
+
+    let mut changes: Vec<RankingChange> = top15
+        .iter()
+        .map(|r| change(&r.contractor, previous_by_name.get(r.contractor.as_str()).copied(), Some(r)))
+        .collect();
+    let mut dropped: Vec<_> = previous.iter().filter(|p| !in_top15.contains(p.contractor.as_str())).collect();
+    dropped.sort_by_key(|p| p.rank);
+    changes.extend(
+        dropped
+            .into_iter()
+            .map(|p| change(&p.contractor, Some(p), current_by_name.get(p.contractor.as_str()).copied())),
+    );
+    changes
+}
+
+/// Minimum change in average delay (days) between a contractor's earliest and latest
+/// funding years before the trend counts as improving or declining.
+const TREND_THRESHOLD_DAYS: f64 = 15.0;
+
+/// Compares a contractor's average delay in its earliest funding year against its latest.
+fn calculate_delay_trend(group: &[&Project], policy: MissingDelayPolicy) -> String {
+    let mut year_map: HashMap<i32, Vec<&Project>> = HashMap::new();
+    for p in group {
+        year_map.entry(p.funding_year).or_default().push(p);
+    }
+    // Only years with delay data can anchor the comparison
+    year_map.retain(|_, projects| !collect_delays(projects.iter().copied(), policy).is_empty());
+
+    let (Some(first_year), Some(last_year)) = (year_map.keys().min(), year_map.keys().max()) else {
+        return "Insufficient".to_string();
+    };
+    if first_year == last_year {
+        return "Insufficient".to_string();
+    }
+
+    let first_delay = calculate_avg_delay(&year_map[first_year], policy);
+    let last_delay = calculate_avg_delay(&year_map[last_year], policy);
+    delay_trend_label(first_delay, last_delay)
+}
+
+fn delay_trend_label(first_delay: f64, last_delay: f64) -> String {
+    if last_delay < first_delay - TREND_THRESHOLD_DAYS {
+        "Improving".to_string()
+    } else if last_delay > first_delay + TREND_THRESHOLD_DAYS {
+        "Declining".to_string()
+    } else {
+        "Stable".to_string()
+    }
+}
+
+/// Lists contractors by how many distinct regions they worked in, most widespread first.
+fn calculate_contractor_reach(projects: &[Project]) -> Vec<ContractorReach> {
+    let mut reach: Vec<ContractorReach> = group_by_contractor(projects)
+        .into_iter()
+        .map(|(contractor, group)| ContractorReach {
+            contractor,
+            region_count: group.iter().map(|p| &p.region).collect::<HashSet<_>>().len(),
+            total_projects: group.len() as i32,
+            total_cost: sum_money(group.iter().map(|p| p.contract_cost)),
+        })
+        .collect();
+    sort_contractor_reach(&mut reach);
+    reach
+}
+
+/// Contractors active in only one of the first and last funding years in the data.
+fn calculate_contractor_churn(projects: &[Project]) -> Vec<ContractorChurn> {
+    let Some(span) = churn_span(&count_contractors_by_year(projects)) else {
+        return Vec::new();
+    };
+    let mut churn: Vec<ContractorChurn> = group_by_contractor(projects)
+        .into_iter()
+        .filter_map(|(contractor, group)| {
+            let years: BTreeSet<i32> = group.iter().map(|p| p.funding_year).collect();
+            churn_row(&contractor, &years, span, group.len(), sum_money(group.iter().map(|p| p.contract_cost)))
+        })
+        .collect();
+    sort_contractor_churn(&mut churn);
+    churn
+}
+
+/// The first and last funding years with projects, or None with fewer than two years,
+/// when there is nothing to compare.
+fn churn_span<V>(by_year: &BTreeMap<i32, V>) -> Option<(i32, i32)> {
+    let first = *by_year.keys().next()?;
+    let last = *by_year.keys().next_back()?;
+    (first < last).then_some((first, last))
+}
+
+/// The churn row for one contractor, or None when it was active in both end years or neither.
+fn churn_row(
+    contractor: &str,
+    years: &BTreeSet<i32>,
+    (first, last): (i32, i32),
+    projects: usize,
+    total_cost: f64,
+) -> Option<ContractorChurn> {
+    let status = match (years.contains(&first), years.contains(&last)) {
+        (true, false) => "exit",
+        (false, true) => "entrant",
+        _ => return None,
+    };
+    Some(ContractorChurn {
+        contractor: contractor.to_string(),
+        status: status.to_string(),
+        active_years: years.iter().map(|y| y.to_string()).collect::<Vec<_>>().join(";"),
+        total_projects: projects as i32,
+        total_cost,
+    })
+}
+
+/// Exits first, then entrants, each by total cost descending.
+fn sort_contractor_churn(churn: &mut [ContractorChurn]) {
+    churn.sort_by(|a, b| {
+        b.status
+            .cmp(&a.status)
+            .then(b.total_cost.partial_cmp(&a.total_cost).unwrap_or(Ordering::Equal))
+            .then(a.contractor.cmp(&b.contractor))
+    });
+}
+
+fn count_contractors_by_year(projects: &[Project]) -> BTreeMap<i32, usize> {
+    let pairs: HashSet<(i32, &str)> = projects.iter().map(|p| (p.funding_year, p.contractor.as_str())).collect();
+    let mut counts = BTreeMap::new();
+    for (year, _) in pairs {
+        *counts.entry(year).or_default() += 1;
+    }
+    counts
+}
+
+/// Orders by region count, then total cost, both descending.
+fn sort_contractor_reach(reach: &mut [ContractorReach]) {
+    reach.sort_by(|a, b| {
+        b.region_count
+            .cmp(&a.region_count)
+            .then(b.total_cost.partial_cmp(&a.total_cost).unwrap_or(Ordering::Equal))
+    });
+}
+
+/// Contractors whose project count falls in the near-miss band below the Report 2 threshold.
+fn calculate_near_miss_contractors(projects: &[Project], config: &Config) -> Vec<NearMissContractor> {
+    let lower = config.near_miss_min;
+    let upper = config.near_miss_upper();
+
+    let mut near_miss: Vec<NearMissContractor> = group_by_contractor(projects)
+        .into_iter()
+        .filter(|(_, group)| (lower..=upper).contains(&group.len()))
+        .map(|(contractor, group)| NearMissContractor {
+            contractor,
+            num_projects: group.len() as i32,
+            total_cost: sum_money(group.iter().map(|p| p.contract_cost)),
+        })
+        .collect();
+
+    near_miss.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap_or(Ordering::Equal));
+    near_miss
+}
+
+/// Buckets projects by approved budget using `BUDGET_TIERS`. Every tier is listed,
+/// including empty ones. Savings rates skip projects with a zero budget.
+fn calculate_budget_tiers(projects: &[Project], config: &Config) -> Vec<BudgetTierAnalysis> {
+    let mut tier_groups: Vec<Vec<&Project>> = vec![Vec::new(); BUDGET_TIERS.len()];
+    for p in projects {
+        tier_groups[budget_tier_index(p.approved_budget)].push(p);
+    }
+
+    BUDGET_TIERS
+        .iter()
+        .zip(tier_groups)
+        .map(|((label, _), group)| {
+            let rates: Vec<f64> = group
+                .iter()
+                .filter(|p| p.approved_budget != 0.0)
+                .map(|p| p.cost_savings / p.approved_budget * 100.0)
+                .collect();
+            let avg_savings_rate = if rates.is_empty() {
+                0.0
+            } else {
+                rates.iter().sum::<f64>() / rates.len() as f64
+            };
+            let overrun_count = group
+                .iter()
+                .filter(|p| config.overrun_margin.is_overrun(p))
+                .count();
+            let overrun_rate = if group.is_empty() {
+                0.0
+            } else {
+                (overrun_count as f64 / group.len() as f64) * 100.0
+            };
+
+            BudgetTierAnalysis {
+                budget_tier: label.to_string(),
+                project_count: group.len() as i32,
+                total_budget: sum_money(group.iter().map(|p| p.approved_budget)),
+                avg_savings_rate,
+                overrun_rate,
+            }
+        })
+        .collect()
+}
+
+fn budget_tier_index(approved_budget: f64) -> usize {
+    BUDGET_TIERS
+        .iter()
+        .position(|(_, upper)| approved_budget < *upper)
+        .unwrap_or(BUDGET_TIERS.len() - 1)
+}
+
+fn calculate_performance_metrics(projects: &[Project], config: &Config) -> Vec<PerformanceMetrics> {
+    let mut year_type_map: HashMap<(i32, String), Vec<&Project>> = HashMap::new();
+    for p in projects {
+        year_type_map
+            .entry((p.funding_year, p.type_of_work.clone()))
+            .or_default()
+            .push(p);
+    }
+
+    let mut report3 = Vec::new();
+
+    for ((year, work_type), group) in &year_type_map {
+        let total_projects = group.len() as i32;
+        let total_savings = sum_money(group.iter().map(|p| p.cost_savings));
+        let avg_savings = total_savings / total_projects as f64;
+        let overrun_count = group
+            .iter()
+            .filter(|p| config.overrun_margin.is_overrun(p))
+            .count();
+        let overrun_rate = (overrun_count as f64 / total_projects as f64) * 100.0;
+        // Zero-budget projects have no meaningful utilization, so they are left out
+        let utilizations: Vec<f64> = group
+            .iter()
+            .filter(|p| p.approved_budget != 0.0)
+            .map(|p| p.contract_cost / p.approved_budget * 100.0)
+            .collect();
+        let avg_utilization_pct = if utilizations.is_empty() {
+            0.0
+        } else {
+            utilizations.iter().sum::<f64>() / utilizations.len() as f64
+        };
+
+        report3.push(PerformanceMetrics {
+            funding_year: *year,
+            type_of_work: work_type.clone(),
+            total_projects,
+            total_savings,
+            avg_savings,
+            overrun_rate,
+            avg_utilization_pct,
+            yoy_change: 0.0,
+            sample_adequacy: sample_adequacy(group.len(), config),
+        });
+    }
+
+    finish_report_3(&mut report3, &config.yoy_baselines);
+    report3
+}
+
+/// How far a Report 3 cell's rates can be trusted, judged only by how many projects back it.
+pub fn sample_adequacy(total_projects: usize, config: &Config) -> String {
+    if total_projects >= config.adequate_sample_min {
+        "adequate".to_string()
+    } else if total_projects >= config.limited_sample_min {
+        "limited".to_string()
+    } else {
+        "sparse".to_string()
+    }
+}
+
+/// Fills in YoY changes from the previous year's row and applies the Report 3 sort order.
+fn finish_report_3(report3: &mut [PerformanceMetrics], baselines: &YoyBaselines) {
+    let savings_map: HashMap<(i32, String), f64> = report3
+        .iter()
+        .map(|row| ((row.funding_year, row.type_of_work.clone()), row.avg_savings))
+        .collect();
+
+    // Calculate YoY (2021 has no previous year unless --context-years loaded one)
+    for row in report3.iter_mut() {
+        let prev_year = row.funding_year - 1;
+        let prev_year_savings = savings_map
+            .get(&(prev_year, row.type_of_work.clone()))
+            .copied()
+            .or_else(|| baselines.avg_savings(prev_year, &row.type_of_work));
+        row.yoy_change = yoy_change(row.avg_savings, prev_year_savings);
+    }
+
+    // Sort per REQ-0008
+    report3.sort_by(|a, b| {
+        a.funding_year.cmp(&b.funding_year).then_with(|| {
+            b.avg_savings
+                .partial_cmp(&a.avg_savings)
+                .unwrap_or(Ordering::Equal)
+        })
+    });
+}
+
+/// Percentage change from `previous` to `current`. With no previous year the change is 0; from
+/// a zero baseline any increase counts as 100%.
+fn yoy_change(current: f64, previous: Option<f64>) -> f64 {
+    match previous {
+        Some(previous) if previous != 0.0 => (current - previous) / previous.abs() * 100.0,
+        Some(_) if current > 0.0 => 100.0,
+        _ => 0.0,
+    }
+}
+
+fn calculate_region_budget_yoy(projects: &[Project], baselines: &YoyBaselines) -> Vec<RegionBudgetYoy> {
+    let mut budgets: BTreeMap<(String, i32), Centavos> = BTreeMap::new();
+    for p in projects {
+        *budgets.entry((p.region.clone(), p.funding_year)).or_default() += Centavos::from_pesos(p.approved_budget);
+    }
+    finish_region_budget_yoy(budgets, baselines)
+}
+
+/// Builds the regional budget rows, ordered by region then year, from per-(region, year) totals.
+fn finish_region_budget_yoy(budgets: BTreeMap<(String, i32), Centavos>, baselines: &YoyBaselines) -> Vec<RegionBudgetYoy> {
+    budgets
+        .iter()
+        .map(|((region, year), total_budget)| {
+            let previous = budgets
+                .get(&(region.clone(), year - 1))
+                .map(|budget| budget.to_pesos())
+                .or_else(|| baselines.region_budget(region, year - 1));
+            RegionBudgetYoy {
+                region: region.clone(),
+                funding_year: *year,
+                total_budget: total_budget.to_pesos(),
+                yoy_change: yoy_change(total_budget.to_pesos(), previous),
+            }
+        })
+        .collect()
+}
+
+/// `report1`, when already built, supplies the national efficiency score's regional scores.
+fn calculate_summary_json(projects: &[Project], report1: Option<&[InfrastructureTrends]>, config: &Config) -> SummaryJson {
+    let delays = collect_delays(projects, config.missing_delay);
+    let global_avg_delay = capped_average(&delays, None);
+
+    // provinces (per REQ-0009)
+    let provinces: HashSet<&str> = projects
+        .iter()
+        .map(|p| p.province.as_str())
+        .filter(|province| !province.is_empty())
+        .collect();
+    let regions: HashSet<&str> = projects.iter().map(|p| p.region.as_str()).collect();
+    let total_provinces = province_count(provinces.len(), regions.len());
+
+    let mut budget_by_year: BTreeMap<i32, Centavos> = BTreeMap::new();
+    for p in projects {
+        *budget_by_year.entry(p.funding_year).or_default() += Centavos::from_pesos(p.approved_budget);
+    }
+
+    // Empty when no projects were loaded, giving 0 for both figures
+    let overruns: Vec<&Project> = projects
+        .iter()
+        .filter(|p| config.overrun_margin.is_overrun(p))
+        .collect();
+
+    SummaryJson {
+        total_projects_analyzed: projects.len(),
+        total_budget_analyzed: sum_money(projects.iter().map(|p| p.approved_budget)),
+        global_avg_delay,
+        national_efficiency_score: match report1 {
+            Some(report1) => national_efficiency_score(report1),
+            None => regional_efficiency_score(projects, config),
+        },
+        total_contractors: count_ranked_contractors(projects, config),
+        total_provinces,
+        budget_by_year: budget_by_year.into_iter().map(|(year, budget)| (year, budget.to_pesos())).collect(),
+        total_overrun_amount: sum_money(overruns.iter().map(|p| p.contract_cost - p.approved_budget)),
+        overrun_project_count: overruns.len(),
+        budget_gini: gini(&mut projects.iter().map(|p| p.approved_budget).collect::<Vec<_>>()),
+        contractor_hhi: contractor_hhi(group_by_contractor(projects).values().map(|group| {
+            sum_money(group.iter().map(|p| p.contract_cost))
+        })),
+        island_breakdown: calculate_island_breakdown(projects, config),
+        delay_cap_days: None,
+        dataset_sha256: String::new(),
+    }
+}
+
+/// Budget-weighted average of the Report 1 efficiency scores: Σ(scoreᵢ·budgetᵢ) / Σbudgetᵢ.
+/// Regions with a non-positive budget carry no weight; 0 when no region has any.
+pub fn national_efficiency_score(report1: &[InfrastructureTrends]) -> f64 {
+    budget_weighted_score(report1.iter().map(|r| (r.efficiency_score, r.total_budget)))
+}
+
+/// `national_efficiency_score` without building the Report 1 rows: per (region, main island)
+/// only the budget, median savings and average delay that the score needs are computed.
+fn regional_efficiency_score(projects: &[Project], config: &Config) -> f64 {
+    let mut regions: BTreeMap<(&str, &str), Vec<&Project>> = BTreeMap::new();
+    for p in projects {
+        regions.entry((p.region.as_str(), p.main_island.as_str())).or_default().push(p);
+    }
+    budget_weighted_score(regions.values().map(|group| {
+        let avg_delay = capped_average(&collect_delays(group.iter().copied(), config.missing_delay), config.delay_cap);
+        let score = calculate_efficiency_score(calculate_median_savings(group), avg_delay.unwrap_or(0.0));
+        (score, sum_money(group.iter().map(|p| p.approved_budget)))
+    }))
+}
+
+/// Σ(scoreᵢ·budgetᵢ) / Σbudgetᵢ over (score, budget) pairs, skipping non-positive budgets.
+fn budget_weighted_score(scores: impl Iterator<Item = (f64, f64)>) -> f64 {
+    let weighted: Vec<(f64, f64)> = scores.filter(|(_, budget)| *budget > 0.0).collect();
+    let total_budget: f64 = weighted.iter().map(|(_, budget)| budget).sum();
+    if total_budget == 0.0 {
+        return 0.0;
+    }
+    weighted.iter().map(|(score, budget)| score * budget).sum::<f64>() / total_budget
+}
+
+/// Herfindahl-Hirschman Index: the sum of squared percentage shares of the total.
+/// 0 when the total is zero, since no shares exist.
+fn contractor_hhi(totals: impl Iterator<Item = f64> + Clone) -> f64 {
+    let grand_total = sum_money(totals.clone());
+    if grand_total == 0.0 {
+        return 0.0;
+    }
+    totals.map(|t| (t / grand_total * 100.0).powi(2)).sum()
+}
+
+/// Gini coefficient by the sorted-cumulative formula: with values sorted ascending and
+/// ranked i = 1..n, G = 2·Σ(i·xᵢ) / (n·Σxᵢ) − (n + 1) / n. 0 for no values, a single value or a
+/// zero total. Sorts `values` in place.
+///
+/// ```
+/// // 2·(1·1 + 2·2 + 3·3 + 4·4) / (4·10) − 5/4 = 0.25
+/// assert_eq!(mco2_rust::gini(&mut [4.0, 1.0, 3.0, 2.0]), 0.25);
+/// assert_eq!(mco2_rust::gini(&mut [7.0]), 0.0);
+/// ```
+pub fn gini(values: &mut [f64]) -> f64 {
+    let n = values.len() as f64;
+    let total: f64 = values.iter().sum();
+    if values.len() < 2 || total == 0.0 {
+        return 0.0;
+    }
+    values.sort_by(f64::total_cmp);
+    let ranked_sum: f64 = values.iter().enumerate().map(|(i, v)| (i + 1) as f64 * v).sum();
+    2.0 * ranked_sum / (n * total) - (n + 1.0) / n
+}
+
+/// Reading of an HHI on the usual antitrust bands.
+pub fn hhi_label(hhi: f64) -> &'static str {
+    if hhi > 2500.0 {
+        "highly concentrated"
+    } else if hhi >= 1500.0 {
+        "moderately concentrated"
+    } else {
+        "unconcentrated"
+    }
+}
+
+fn calculate_island_breakdown(projects: &[Project], config: &Config) -> BTreeMap<String, IslandSummary> {
+    let mut island_map: BTreeMap<&str, Vec<&Project>> = BTreeMap::new();
+    for p in projects {
+        island_map.entry(p.main_island.as_str()).or_default().push(p);
+    }
+
+    island_map
+        .into_iter()
+        .map(|(island, group)| {
+            let delays = collect_delays(group.iter().copied(), config.missing_delay);
+            let avg_delay = capped_average(&delays, None);
+            let summary = IslandSummary {
+                project_count: group.len(),
+                total_budget: sum_money(group.iter().map(|p| p.approved_budget)),
+                total_savings: sum_money(group.iter().map(|p| p.cost_savings)),
+                avg_delay,
+            };
+            (island.to_string(), summary)
+        })
+        .collect()
+}
+
+/// Distinct provinces, falling back to distinct regions as a proxy when no project carries a
+/// province (the source file has no Province column).
+fn province_count(provinces: usize, regions: usize) -> usize {
+    if provinces == 0 { regions } else { provinces }
+}
+
+fn calculate_median_savings(group: &[&Project]) -> f64 {
+    let mut savings: Vec<f64> = group.iter().map(|p| p.cost_savings).collect();
+    median(&mut savings)
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let mid = values.len() / 2;
+
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Gathers delays for averaging, applying the missing-delay policy to projects without one.
+pub fn collect_delays<'a>(projects: impl IntoIterator<Item = &'a Project>, policy: MissingDelayPolicy) -> Vec<i64> {
+    projects
+        .into_iter()
+        .filter_map(|p| policy_delay(p, policy))
+        .collect()
+}
+
+fn policy_delay(project: &Project, policy: MissingDelayPolicy) -> Option<i64> {
+    match policy {
+        MissingDelayPolicy::Exclude => project.completion_delay_days,
+        MissingDelayPolicy::TreatAsZero => Some(project.completion_delay_days.unwrap_or(0)),
+    }
+}
+
+/// Median of cost savings where each project is weighted by its approved budget.
+///
+/// Savings are sorted ascending and their budgets accumulated; the weighted median is the
+/// first value at which the running weight reaches half of the total. When the halfway
+/// point falls exactly on a boundary, the two neighbouring values are averaged (the same
+/// convention as the even-length plain median). Negative budgets carry no weight, and a
+/// group with no positive weight falls back to the plain median.
+fn calculate_weighted_median_savings(group: &[&Project]) -> f64 {
+    let mut weighted: Vec<(f64, f64)> = group
+        .iter()
+        .map(|p| (p.cost_savings, p.approved_budget))
+        .collect();
+    weighted_median(&mut weighted)
+}
+
+/// Weighted median over (value, weight) pairs; see `calculate_weighted_median_savings`.
+fn weighted_median(weighted: &mut [(f64, f64)]) -> f64 {
+    for pair in weighted.iter_mut() {
+        pair.1 = pair.1.max(0.0);
+    }
+    let total_weight: f64 = weighted.iter().map(|(_, w)| w).sum();
+    if total_weight <= 0.0 {
+        let mut values: Vec<f64> = weighted.iter().map(|(v, _)| *v).collect();
+        return median(&mut values);
+    }
+
+    weighted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+    let half = total_weight / 2.0;
+    let mut cumulative = 0.0;
+    for (i, (savings, weight)) in weighted.iter().enumerate() {
+        cumulative += weight;
+        if cumulative > half {
+            return *savings;
+        }
+        if cumulative == half {
+            let next = weighted[i + 1..].iter().find(|(_, w)| *w > 0.0);
+            return next.map_or(*savings, |(n, _)| (savings + n) / 2.0);
+        }
+    }
+    weighted.last().map_or(0.0, |(s, _)| *s)
+}
+
+fn calculate_avg_delay(group: &[&Project], policy: MissingDelayPolicy) -> f64 {
+    capped_average(&collect_delays(group.iter().copied(), policy), None).unwrap_or(0.0)
+}
+
+/// Mean delay with each value first limited to `cap` (winsorized from above); None when empty.
+fn capped_average(delays: &[i64], cap: Option<i64>) -> Option<f64> {
+    if delays.is_empty() {
+        return None;
+    }
+    let cap = cap.unwrap_or(i64::MAX);
+    Some(delays.iter().map(|&d| d.min(cap)).sum::<i64>() as f64 / delays.len() as f64)
+}
+
+/// Nearest-rank percentile: the smallest value with at least `percentile`% of values at or
+/// below it. None for an empty slice.
+pub fn percentile_value(values: &mut [i64], percentile: f64) -> Option<i64> {
+    values.sort_unstable();
+    let rank = (percentile / 100.0 * values.len() as f64).ceil() as usize;
+    values.get(rank.max(1) - 1).copied()
+}
+
+// I/O Helpers
+
+/// An output file name paired with the closure that writes to it.
+type WriteJob<'a> = (String, Box<dyn FnOnce(&str) -> Result<(), Box<dyn Error>> + Send + 'a>);
+
+/// Queues one write job per file `format` calls for, named `stem` plus the format's extension.
+fn push_table_jobs<'a, T: Serialize + DeserializeOwned + Sync>(
+    jobs: &mut Vec<WriteJob<'a>>,
+    stem: &str,
+    data: &'a [T],
+    format: TableFormat,
+    verify: bool,
+) {
+    for &(extension, delimiter) in format.outputs() {
+        jobs.push((
+            format!("{}.{}", stem, extension),
+            Box::new(move |path| {
+                write_delimited(data, path, delimiter)?;
+                if verify {
+                    verify_round_trip(data, path, delimiter)?;
+                }
+                Ok(())
+            }),
+        ));
+    }
+}
+
+/// `--verify-output`: reads a written table back into its row type and checks that it
+/// serializes to exactly what was written, catching columns that don't survive the trip
+/// (renamed or skipped fields, values that no longer parse).
+fn verify_round_trip<T: Serialize + DeserializeOwned>(data: &[T], filename: &str, delimiter: u8) -> Result<(), Box<dyn Error>> {
+    let read_back: Vec<T> = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_path(filename)?
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("round-trip check failed reading back: {}", e))?;
+    if read_back.len() != data.len() {
+        return Err(format!("round-trip check failed: wrote {} rows, read back {}", data.len(), read_back.len()).into());
+    }
+    let written = delimited_text(data, delimiter)?;
+    let reread = delimited_text(&read_back, delimiter)?;
+    if let Some((line, (w, r))) = written.lines().zip(reread.lines()).enumerate().find(|(_, (w, r))| w != r) {
+        return Err(format!("round-trip check failed on line {}: wrote \"{}\", read back \"{}\"", line + 1, w, r).into());
+    }
+    Ok(())
+}
+
+/// Rows serialized exactly as `write_delimited` writes them.
+fn delimited_text<T: Serialize>(data: &[T], delimiter: u8) -> Result<String, Box<dyn Error>> {
+    let mut writer = WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+    for row in data {
+        writer.serialize(row)?;
+    }
+    Ok(String::from_utf8(writer.into_inner().map_err(|e| e.to_string())?)?)
+}
+
+/// Runs each write on its own scoped thread so slow disks don't serialize the outputs.
+/// Every job runs to completion, so one locked file doesn't cost the others; failures are
+/// collected into one combined error.
+fn write_outputs(jobs: Vec<WriteJob>) -> Result<(), Box<dyn Error>> {
+    let total = jobs.len();
+    let errors: Vec<String> = std::thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .into_iter()
+            .map(|(name, job)| {
+                // Errors are turned into strings on the worker, since Box<dyn Error> isn't Send
+                scope.spawn(move || job(&name).map_err(|e| write_failure_message(&name, e.as_ref())))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| match handle.join() {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e),
+                Err(_) => Some("a writer thread panicked".to_string()),
+            })
+            .collect()
+    });
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {} output files could not be written; the rest were saved.\n  {}",
+            errors.len(),
+            total,
+            errors.join("\n  ")
+        )
+        .into())
+    }
+}
+
+/// Describes a failed output write, pointing at the usual culprit when the file is locked:
+/// a spreadsheet program holding it open (Windows refuses to replace an open file).
+fn write_failure_message(name: &str, e: &(dyn Error + 'static)) -> String {
+    let mut source = Some(e);
+    while let Some(err) = source {
+        if let Some(io_err) = err.downcast_ref::<io::Error>() {
+            // 32 is ERROR_SHARING_VIOLATION, which std doesn't map to an ErrorKind
+            let locked = matches!(io_err.kind(), io::ErrorKind::PermissionDenied | io::ErrorKind::ResourceBusy)
+                || (cfg!(windows) && io_err.raw_os_error() == Some(32));
+            if locked {
+                return format!("Could not write {} — is it open in another program? ({})", name, io_err);
+            }
+            break;
+        }
+        source = err.source();
+    }
+    format!("Could not write {}: {}", name, e)
+}
+
+/// Writes through a temporary file that is renamed into place only on success, so a failed
+/// or interrupted write never leaves a truncated output behind.
+pub fn write_atomically(
+    filename: &str,
+    write: impl FnOnce(&mut io::BufWriter<File>) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let tmp_path = format!("{}.tmp", filename);
+    let mut out = io::BufWriter::new(File::create(&tmp_path)?);
+    let mut result = write(&mut out).and_then(|()| Ok(out.flush()?));
+    drop(out);
+    if result.is_ok() && was_interrupted() {
+        result = Err("interrupted".into());
+    }
+    // A failed rename (e.g. the target is locked) must not leave the temporary file behind either
+    let result = result.and_then(|()| Ok(std::fs::rename(&tmp_path, filename)?));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+pub fn write_csv<T: Serialize>(data: &[T], filename: &str) -> Result<(), Box<dyn Error>> {
+    write_delimited(data, filename, b',')
+}
+
+/// Like `write_csv`, with a caller-chosen field delimiter (e.g. a tab for TSV).
+fn write_delimited<T: Serialize>(data: &[T], filename: &str, delimiter: u8) -> Result<(), Box<dyn Error>> {
+    write_atomically(filename, |out| {
+        let mut writer = WriterBuilder::new().delimiter(delimiter).from_writer(out);
+        for row in data {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// One row of `summary_history.csv` as (header, values): the timestamp, then every scalar
+/// field of `summary.json` in name order, taken from `SummaryJson` itself so new summary
+/// figures reach the history too. The per-year and per-island maps are left out, since
+/// their keys vary from run to run.
+fn summary_history_row(summary: &SummaryJson, timestamp: &str) -> Result<(Vec<String>, Vec<String>), Box<dyn Error>> {
+    let serde_json::Value::Object(mut fields) = serde_json::to_value(summary)? else {
+        return Err("summary did not serialize to a JSON object".into());
+    };
+    // Left out of summary.json when no cap applies, but the history needs fixed columns
+    fields.entry("delay_cap_days").or_insert(serde_json::Value::Null);
+    let mut header = vec!["timestamp".to_string()];
+    let mut values = vec![timestamp.to_string()];
+    for (name, value) in fields {
+        let value = match value {
+            serde_json::Value::Object(_) | serde_json::Value::Array(_) => continue,
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(text) => text,
+            scalar => scalar.to_string(),
+        };
+        header.push(name);
+        values.push(value);
+    }
+    Ok((header, values))
+}
+
+/// Appends one timestamped summary row, writing the header only when the file is new or empty.
+/// A file whose header differs (e.g. from a version with other summary fields) is moved aside
+/// and a new one started, so columns never misalign.
+fn append_summary_history(summary: &SummaryJson, filename: &str) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Local::now();
+    let (header, values) = summary_history_row(summary, &now.format("%Y-%m-%d %H:%M:%S").to_string())?;
+    // A missing file has no header to compare; any other open error surfaces below
+    if let Ok(mut reader) = csv::Reader::from_path(filename) {
+        let existing = reader.headers()?;
+        if !existing.is_empty() && existing.iter().ne(header.iter().map(String::as_str)) {
+            let stem = filename.strip_suffix(".csv").unwrap_or(filename);
+            let archived = format!("{}_until_{}.csv", stem, now.format("%Y%m%d_%H%M%S"));
+            std::fs::rename(filename, &archived)?;
+            println!("WARNING: {} had different columns; moved it to {} and started a new history.", filename, archived);
+        }
+    }
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filename)?;
+    let is_new = file.metadata()?.len() == 0;
+
+    let mut writer = WriterBuilder::new().from_writer(file);
+    if is_new {
+        writer.write_record(&header)?;
+    }
+    writer.write_record(&values)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_json<T: Serialize>(data: &T, filename: &str) -> Result<(), Box<dyn Error>> {
+    write_atomically(filename, |out| Ok(serde_json::to_writer_pretty(out, data)?))
+}
+
+/// Inline stylesheet for the HTML reports, so each page stands alone.
+const HTML_STYLE: &str = "body{font-family:Segoe UI,Helvetica,Arial,sans-serif;margin:2em;color:#222}\
+h1{font-size:1.4em;margin-bottom:.2em}p.note{margin:.2em 0;color:#555}\
+table{border-collapse:collapse;margin-top:1em;font-size:.9em}\
+th,td{border:1px solid #ccc;padding:.35em .6em;text-align:left}\
+th{background:#1f4e79;color:#fff}tr:nth-child(even) td{background:#f3f6fa}td.num{text-align:right}";
+
+/// Writes a report table as a self-contained HTML page. The columns and values are exactly
+/// those of the CSV export; numeric cells are right-aligned and flags are coloured.
+fn write_html_table<T: Serialize>(
+    data: &[T],
+    title: &str,
+    context: &str,
+    config: &Config,
+    path: &str,
+) -> Result<(), Box<dyn Error>> {
+    let csv_text = delimited_text(data, b',')?;
+    let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+    let headers = if data.is_empty() { StringRecord::new() } else { reader.headers()?.clone() };
+    let mut notes = Vec::new();
+    write_filter_notes(&mut notes, config)?;
+    write_delay_cap_note(&mut notes, config)?;
+
+    write_atomically(path, |out| {
+        writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>\n<style>{}</style>\n</head>\n<body>", html_escape(title), HTML_STYLE)?;
+        writeln!(out, "<h1>{}</h1>", html_escape(title))?;
+        writeln!(out, "<p class=\"note\">{}</p>", html_escape(context))?;
+        for note in String::from_utf8_lossy(&notes).lines() {
+            // The console notes come in parentheses
+            let note = note.trim_start_matches('(').trim_end_matches(')');
+            writeln!(out, "<p class=\"note\">{}</p>", html_escape(note))?;
+        }
+        if data.is_empty() {
+            writeln!(out, "<p>No rows.</p>")?;
+        } else {
+            writeln!(out, "<table>\n<thead><tr>")?;
+            for header in &headers {
+                writeln!(out, "<th>{}</th>", html_escape(&column_heading(header)))?;
+            }
+            writeln!(out, "</tr></thead>\n<tbody>")?;
+            for record in reader.records() {
+                write!(out, "<tr>")?;
+                for cell in &record? {
+                    write!(out, "{}", html_cell(cell))?;
+                }
+                writeln!(out, "</tr>")?;
+            }
+            writeln!(out, "</tbody>\n</table>")?;
+        }
+        writeln!(out, "</body>\n</html>")?;
+        Ok(())
+    })
+}
+
+/// One `<td>`: numbers right-aligned, risk and savings flags coloured with inline styles.
+fn html_cell(cell: &str) -> String {
+    let style = match cell {
+        "High Risk" | "High Savings" | "Low Savings" => " style=\"color:#b00020;font-weight:bold\"",
+        "Low Risk" => " style=\"color:#1b7f3b;font-weight:bold\"",
+        _ => "",
+    };
+    let class = if !cell.is_empty() && cell.parse::<f64>().is_ok() { " class=\"num\"" } else { "" };
+    format!("<td{}{}>{}</td>", class, style, html_escape(cell))
+}
+
+/// "savings_rate_pct" -> "Savings Rate %", for table headings meant for people.
+fn column_heading(column: &str) -> String {
+    column
+        .split('_')
+        .map(|word| match word {
+            "pct" => "%".to_string(),
+            _ => {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Contractors the synthetic projects are spread over, round-robin.
+const SYNTHETIC_CONTRACTORS: usize = 40;
+const SYNTHETIC_WORK_TYPES: [&str; 2] = ["Construction of Flood Mitigation Structure", "Construction of Revetment"];
+
+/// `n` made-up projects whose figures follow fixed cycles over the index, so every total
+/// has a closed form (see `self_test_checks`). Project i has:
+/// - funding year 2021 + i mod 3, work type i mod 2, region i mod 17, contractor i mod 40
+/// - approved budget 1,000,000 + 1,000 * (i mod 100)
+/// - savings 2,000 * (i mod 10), except every tenth project, which overruns by 5,000
+/// - a delay of i mod 60 days
+pub fn synthetic_projects(n: usize) -> Vec<Project> {
+    let start = NaiveDate::from_ymd_opt(2022, 1, 3).expect("valid date");
+    (0..n)
+        .map(|i| {
+            let (region, island) = REGION_ISLANDS[i % REGION_ISLANDS.len()];
+            let approved_budget = 1_000_000.0 + 1_000.0 * (i % 100) as f64;
+            let cost_savings = if i % 10 == 0 { -5_000.0 } else { 2_000.0 * (i % 10) as f64 };
+            let delay = (i % 60) as i64;
+            Project {
+                project_id: format!("SYN-{}", i),
+                region: region.to_string(),
+                province: String::new(),
+                main_island: island.to_string(),
+                contractor: format!("Synthetic Contractor {:02}", i % SYNTHETIC_CONTRACTORS),
+                funding_year: 2021 + (i % 3) as i32,
+                type_of_work: SYNTHETIC_WORK_TYPES[i % SYNTHETIC_WORK_TYPES.len()].to_string(),
+                approved_budget,
+                contract_cost: approved_budget - cost_savings,
+                cost_savings,
+                start_date: Some(start),
+                end_date: Some(start + chrono::Duration::days(delay)),
+                completion_delay_days: Some(delay),
+            }
+        })
+        .collect()
+}
+
+/// One self-test comparison: a computed figure against its closed-form value.
+#[derive(Debug)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+impl SelfTestCheck {
+    pub fn passed(&self) -> bool {
+        (self.expected - self.actual).abs() <= 1e-9 * self.expected.abs().max(1.0)
+    }
+}
+
+/// Sum of i mod k for i in 0..n.
+fn sum_of_residues(n: usize, k: usize) -> f64 {
+    let (full, rest) = (n / k, n % k);
+    (full * k * (k - 1) / 2 + rest * rest.saturating_sub(1) / 2) as f64
+}
+
+/// Compares reports built from `synthetic_projects(n)` with the default settings against
+/// values worked out from the cycles the projects follow.
+pub fn self_test_checks(n: usize, reports: &Reports, config: &Config) -> Vec<SelfTestCheck> {
+    let overruns = n.div_ceil(10);
+    let per_contractor = n / SYNTHETIC_CONTRACTORS;
+    let ranked_contractors = if per_contractor >= config.min_projects {
+        n.min(SYNTHETIC_CONTRACTORS)
+    } else if per_contractor + 1 >= config.min_projects {
+        n % SYNTHETIC_CONTRACTORS
+    } else {
+        0
+    };
+    let check = |name, expected: f64, actual: f64| SelfTestCheck { name, expected, actual };
+    let summary = &reports.summary;
+    vec![
+        check("projects analyzed", n as f64, summary.total_projects_analyzed as f64),
+        check(
+            "total budget",
+            1_000_000.0 * n as f64 + 1_000.0 * sum_of_residues(n, 100),
+            summary.total_budget_analyzed,
+        ),
+        check(
+            "global average delay",
+            if n == 0 { 0.0 } else { sum_of_residues(n, 60) / n as f64 },
+            summary.global_avg_delay.unwrap_or(0.0),
+        ),
+        check("overrun projects", overruns as f64, summary.overrun_project_count as f64),
+        check("total overrun amount", 5_000.0 * overruns as f64, summary.total_overrun_amount),
+        check("ranked contractors", ranked_contractors as f64, summary.total_contractors as f64),
+        check("Report 1 rows", n.min(REGION_ISLANDS.len()) as f64, reports.report1.len() as f64),
+        // Year (i mod 3) and work type (i mod 2) together cycle every 6 projects
+        check("Report 3 rows", n.min(6) as f64, reports.report3.len() as f64),
+        check(
+            "Report 3 total savings",
+            2_000.0 * sum_of_residues(n, 10) - 5_000.0 * overruns as f64,
+            // + 0.0 so an empty sum shows as 0.00 rather than -0.00
+            reports.report3.iter().map(|r| r.total_savings).sum::<f64>() + 0.0,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_cuts_multibyte_names_on_a_character_boundary() {
+        // 'Ñ' and 'é' are two bytes each and sit right at the cut
+        assert_eq!(truncate("PEÑA BUILDERS", 3), "PEÑ..");
+        assert_eq!(truncate("PEÑA BUILDERS", 2), "PE..");
+        assert_eq!(truncate("José Construction", 4), "José..");
+        assert_eq!(truncate("José", 4), "José");
+    }
+
+    #[test]
+    fn running_median_matches_sorted_median() {
+        // Fixed-seed linear congruential generator, so failures reproduce
+        let mut state: u64 = 0x5EED;
+        let mut next = || {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            // Small range, so repeated values and ties across the two heaps are common
+            ((state >> 33) % 2_001) as f64 - 1_000.0
+        };
+        for len in [1, 2, 7, 8, 99, 100, 1_001] {
+            let values: Vec<f64> = (0..len).map(|_| next()).collect();
+            let mut running = RunningMedian::default();
+            for (i, &value) in values.iter().enumerate() {
+                running.push(value);
+                // Checked after every push, so both odd and even counts are covered
+                let mut seen = values[..=i].to_vec();
+                assert_eq!(running.median(), median(&mut seen), "len {} after {} values", len, i + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_money_accepts_scientific_notation_without_separators() {
+        let expected = Centavos::from_pesos(1_200_000_000.0);
+        assert_eq!(parse_money("1.2E9").unwrap(), expected);
+        assert_eq!(parse_money("1.2e+9").unwrap(), expected);
+        // A decimal comma, not a thousands separator: stripping it would read 1.2E10
+        assert!(parse_money("1,2E9").is_err());
+    }
+
+    #[test]
+    fn parse_money_ignores_each_thousands_separator_style() {
+        let expected = Centavos::parse_decimal("1234567.89").unwrap();
+        for raw in ["1,234,567.89", "1 234 567.89", "1'234'567.89", "1\u{a0}234\u{a0}567.89", "1\u{2019}234\u{2019}567.89", " 1234567.89 "] {
+            assert_eq!(parse_money(raw).unwrap(), expected, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn write_outputs_keeps_going_past_a_failed_file() {
+        let dir = std::env::temp_dir().join(format!("mco2_rust_unit_{}_write_outputs", std::process::id()));
+        let good = dir.join("good.csv").to_string_lossy().into_owned();
+        // A directory where the file should go, so renaming the finished file into place fails
+        let blocked = dir.join("blocked.csv").to_string_lossy().into_owned();
+        std::fs::create_dir_all(&blocked).unwrap();
+
+        let rows = vec![(1, "a"), (2, "b")];
+        let jobs: Vec<WriteJob> = vec![
+            (blocked.clone(), Box::new(|name: &str| write_csv(&rows, name))),
+            (good.clone(), Box::new(|name: &str| write_csv(&rows, name))),
+        ];
+        let message = write_outputs(jobs).unwrap_err().to_string();
+
+        assert!(message.starts_with("1 of 2 output files could not be written"), "{}", message);
+        assert!(message.contains(&format!("Could not write {}", blocked)), "{}", message);
+        assert!(!message.contains(&good), "{}", message);
+        assert_eq!(std::fs::read_to_string(&good).unwrap(), "1,a\n2,b\n");
+        assert!(!std::path::Path::new(&format!("{}.tmp", blocked)).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn summary_history_keeps_every_scalar_summary_field() {
+        let path = std::env::temp_dir().join(format!("mco2_rust_unit_{}_history.csv", std::process::id()));
+        let filename = path.to_string_lossy().into_owned();
+        let archived_prefix = format!("{}_until_", filename.strip_suffix(".csv").unwrap());
+        let summary = generate_summary(&synthetic_projects(30), &Config { quiet: true, ..Default::default() });
+
+        // An older layout is moved aside rather than appended to
+        std::fs::write(&path, "timestamp,total_projects_analyzed\n2024-01-01 00:00:00,5\n").unwrap();
+        append_summary_history(&summary, &filename).unwrap();
+        append_summary_history(&summary, &filename).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let header: Vec<String> = reader.headers().unwrap().iter().map(str::to_string).collect();
+        for field in [
+            "timestamp", "total_projects_analyzed", "national_efficiency_score", "total_overrun_amount",
+            "overrun_project_count", "contractor_hhi", "budget_gini", "delay_cap_days", "dataset_sha256",
+        ] {
+            assert!(header.iter().any(|h| h == field), "no {} column in {:?}", field, header);
+        }
+        assert!(!header.iter().any(|h| h == "island_breakdown" || h == "budget_by_year"));
+        let rows: Vec<StringRecord> = reader.records().collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows.len(), 2);
+        let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+        assert_eq!(&rows[1][column("total_projects_analyzed")], "30");
+        assert_eq!(&rows[1][column("overrun_project_count")], summary.overrun_project_count.to_string());
+
+        let dir = path.parent().unwrap();
+        let archived: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.to_string_lossy().starts_with(&archived_prefix))
+            .collect();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(std::fs::read_to_string(&archived[0]).unwrap(), "timestamp,total_projects_analyzed\n2024-01-01 00:00:00,5\n");
+        std::fs::remove_file(&archived[0]).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -4,83 +4,142 @@ Language: Rust
 Paradigm(s): Imperative, Functional, Object-Oriented
 ********************/
 
+//! Command-line front end: the interactive menu and batch mode over the `mco2_rust` library.
+
 use std::error::Error;
-use std::fs::File;
-use std::io::{self, Write};
-use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal, Write};
+use std::collections::{BTreeMap, HashSet};
 use chrono::NaiveDate;
-use csv::{StringRecord, WriterBuilder};
-use serde::Serialize;
-use serde_json;
+use csv::WriterBuilder;
+use serde::Deserialize;
 use std::cmp::Ordering;
+use std::sync::atomic;
+use mco2_rust::*;
 
-#[derive(Debug, Clone, Serialize)]
-struct Project {
-    region: String,
-    main_island: String,
-    contractor: String,
-    funding_year: i32,
-    type_of_work: String,
-    approved_budget: f64,
-    contract_cost: f64,
-    cost_savings: f64,
-    completion_delay_days: Option<i64>,
-}
-
-#[derive(Debug, Serialize)]
-struct InfrastructureTrends {
-    region: String,
-    main_island: String,
-    total_budget: f64,
-    median_savings: f64,
-    avg_delay: f64,
-    high_delay_pct: f64,
-    efficiency_score: f64,
-}
-
-#[derive(Debug, Serialize)]
-struct FinancialEfficiencies {
-    rank: i32,
-    contractor: String,
-    total_cost: f64,
-    num_projects: i32,
-    avg_delay: f64,
-    total_savings: f64,
-    reliability_index: f64,
-    risk_flag: String,
-}
-
-#[derive(Debug, Serialize)]
-struct PerformanceMetrics {
-    funding_year: i32,
-    type_of_work: String,
-    total_projects: i32,
-    avg_savings: f64,
-    overrun_rate: f64,
-    yoy_change: f64,
-}
-
-#[derive(Debug, Serialize)]
-struct SummaryJson {
-    total_projects_analyzed: usize,
-    total_budget_analyzed: f64,
-    global_avg_delay: f64,
-    total_contractors: usize,
-    total_provinces: usize,
-}
+mod explorer;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    // Diagnostics go to stderr through `log`; set RUST_LOG=debug (or trace) to see them
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    let config = parse_args(std::env::args().skip(1))?;
+    if config.show_version {
+        println!("mco2_rust {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    if config.write_sample_config {
+        std::fs::write(SAMPLE_CONFIG_FILE, SAMPLE_CONFIG)?;
+        println!("SUCCESS: Sample settings written to {}", SAMPLE_CONFIG_FILE);
+        return Ok(());
+    }
+    if let Some(n) = config.self_test {
+        return run_self_test(n);
+    }
+    if let Some(places) = config.precision {
+        OUTPUT_PRECISION.store(places, atomic::Ordering::SeqCst);
+    }
+    // Let a running load or write stop cleanly; anywhere else Ctrl-C just exits
+    ctrlc::set_handler(|| {
+        if BUSY.load(atomic::Ordering::SeqCst) {
+            INTERRUPTED.store(true, atomic::Ordering::SeqCst);
+        } else {
+            std::process::exit(130);
+        }
+    })?;
     let mut projects: Vec<Project> = Vec::new();
+    // Projects from --context-years, used only as YoY baselines
+    let mut context_projects: Vec<Project> = Vec::new();
+    // In large-file mode the reports are aggregated during load instead of keeping projects
+    let mut streamed_reports: Option<Reports> = None;
+    let mut dataset_sha256 = String::new();
     let mut data_loaded = false;
 
+    // Batch mode: a path plus --generate runs load and reports without the menu
+    if config.generate {
+        let paths = config.dataset_paths();
+        handle_load_data(
+            &config,
+            &paths,
+            &mut projects,
+            &mut context_projects,
+            &mut streamed_reports,
+            &mut dataset_sha256,
+            &mut data_loaded,
+        )?;
+        if !data_loaded {
+            return Err("Failed to load dataset".into());
+        }
+        let kept = streamed_reports
+            .as_ref()
+            .map_or(projects.len(), |r| r.summary.total_projects_analyzed);
+        if kept == 0 {
+            return Err("No projects matched the filters".into());
+        }
+        handle_generate_reports(
+            &config,
+            &projects,
+            &context_projects,
+            streamed_reports.as_ref(),
+            &dataset_sha256,
+            data_loaded,
+        );
+        return Ok(());
+    }
+
     loop {
         print_menu();
-        let choice = get_menu_choice()?;
+        let Some(choice) = get_menu_choice()? else {
+            // stdin closed (e.g. piped input ran out)
+            println!("\nEnd of input; exiting.");
+            break;
+        };
 
         let keep_running = match choice {
-            1 => handle_load_data(&mut projects, &mut data_loaded),
-            2 => handle_generate_reports(&projects, data_loaded),
-            3 => handle_exit(),
+            1 => {
+                let paths = prompt_dataset_paths(&config)?;
+                handle_load_data(
+                    &config,
+                    &paths,
+                    &mut projects,
+                    &mut context_projects,
+                    &mut streamed_reports,
+                    &mut dataset_sha256,
+                    &mut data_loaded,
+                )?
+            }
+            2 => handle_generate_reports(
+                &config,
+                &projects,
+                &context_projects,
+                streamed_reports.as_ref(),
+                &dataset_sha256,
+                data_loaded,
+            ),
+            3 => handle_validate_dataset(&config),
+            4 => handle_round_number_check(&config, &projects, data_loaded),
+            5 => handle_ongoing_projects(&config, &projects, data_loaded),
+            6 => handle_dry_run(&config)?,
+            7 => handle_scatter_export(&config, &projects, data_loaded),
+            8 => handle_contractor_dupes(&config, &projects, data_loaded),
+            9 => handle_trimmed_reports(&config, &projects, &context_projects, &dataset_sha256, data_loaded),
+            10 => handle_at_budget_check(&config, &projects, data_loaded),
+            11 => handle_repeat_contracts(&config, &projects, data_loaded),
+            12 => handle_summary_only(
+                &config,
+                &projects,
+                &context_projects,
+                streamed_reports.as_ref(),
+                &dataset_sha256,
+                data_loaded,
+            ),
+            13 => handle_explore_reports(
+                &config,
+                &projects,
+                &context_projects,
+                streamed_reports.as_ref(),
+                &dataset_sha256,
+                data_loaded,
+            ),
+            14 => handle_exit(),
             _ => handle_invalid_choice(),
         };
 
@@ -91,551 +150,1667 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Builds the run settings: defaults, then the config file (`--config PATH`, or `config.toml`
+/// when present), then the command line, each overriding the one before.
+fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Config, Box<dyn Error>> {
+    let args: Vec<String> = args.collect();
+    let mut config = Config::default();
+    match args.iter().position(|a| a == "--config") {
+        Some(index) => {
+            let path = args.get(index + 1).ok_or("--config requires a value")?;
+            ConfigFile::load(path)?.apply(&mut config)?;
+        }
+        None if std::path::Path::new(CONFIG_FILE).exists() => ConfigFile::load(CONFIG_FILE)?.apply(&mut config)?,
+        None => {}
+    }
+
+    // Lists given on the command line replace the file's lists rather than adding to them
+    let mut cli_paths = Vec::new();
+    let mut cli_work_types = Vec::new();
+    let mut cli_reports = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                args.next();
+            }
+            "--write-sample-config" => config.write_sample_config = true,
+            "--limit" => config.row_limit = Some(next_value(&mut args, &arg)?),
+            "--min-projects" => config.min_projects = next_value(&mut args, &arg)?,
+            "--min-total-cost" => config.min_total_cost = Some(next_value(&mut args, &arg)?),
+            "--min-region-sample" => config.min_region_sample = next_value(&mut args, &arg)?,
+            "--context-years" => config.context_years = next_value(&mut args, &arg)?,
+            "--merge-regions-below" => config.merge_regions_below = Some(next_value(&mut args, &arg)?),
+            "--limited-sample" => config.limited_sample_min = next_value(&mut args, &arg)?,
+            "--adequate-sample" => config.adequate_sample_min = next_value(&mut args, &arg)?,
+            "--near-miss-min" => config.near_miss_min = next_value(&mut args, &arg)?,
+            "--near-miss-max" => config.near_miss_max = Some(next_value(&mut args, &arg)?),
+            "--no-island-correction" => config.correct_islands = false,
+            "--history" => config.append_history = true,
+            "--missing-delay" => config.missing_delay = next_value(&mut args, &arg)?,
+            "--overrun-margin" => config.overrun_margin = next_value(&mut args, &arg)?,
+            "--cap-delays" => config.delay_cap_percentile = Some(next_value(&mut args, &arg)?),
+            "--format" => config.table_format = next_value(&mut args, &arg)?,
+            "--geojson" => config.geojson = true,
+            "--html" => config.html = true,
+            "--verify-output" => config.verify_output = true,
+            "--rank-by" => config.rank_by = next_value(&mut args, &arg)?,
+            "--rank-style" => config.rank_style = next_value(&mut args, &arg)?,
+            "--precision" => config.precision = Some(next_value(&mut args, &arg)?),
+            "--contractor-projects" => config.contractor_projects = Some(next_value(&mut args, &arg)?),
+            "--reports" => {
+                let value: String = next_value(&mut args, &arg)?;
+                for name in split_list(&value) {
+                    cli_reports.push(name.parse()?);
+                }
+            }
+            "--work-type" => {
+                let value: String = next_value(&mut args, &arg)?;
+                cli_work_types.extend(split_list(&value));
+            }
+            "--round-divisor" => config.round_divisor = next_value(&mut args, &arg)?,
+            "--weighted-median" => config.weighted_median = true,
+            "--raw-scores" => config.raw_scores = true,
+            "--vs-average" => config.compare_to_average = true,
+            "--recency-decay" => config.recency_decay = Some(next_value(&mut args, &arg)?),
+            "--savings-flag-high" => config.savings_flag_high = next_value(&mut args, &arg)?,
+            "--savings-flag-low" => config.savings_flag_low = next_value(&mut args, &arg)?,
+            "--compare-ranking" => config.compare_ranking = Some(next_value(&mut args, &arg)?),
+            "--population" => config.population_file = Some(next_value(&mut args, &arg)?),
+            "--ongoing-threshold" => config.ongoing_threshold_days = next_value(&mut args, &arg)?,
+            "--outlier-metric" => config.outlier_metric = next_value(&mut args, &arg)?,
+            "--dupe-threshold" => config.dupe_threshold = next_value(&mut args, &arg)?,
+            "--at-budget-fraction" => config.at_budget_fraction = next_value(&mut args, &arg)?,
+            "--repeat-tolerance" => config.repeat_tolerance = next_value(&mut args, &arg)?,
+            "--start-date-from" => config.start_window.from = Some(next_value(&mut args, &arg)?),
+            "--start-date-to" => config.start_window.to = Some(next_value(&mut args, &arg)?),
+            "--min-kept-fraction" => config.min_kept_fraction = Some(next_value(&mut args, &arg)?),
+            "--anonymize" => config.anonymize = true,
+            "--future-date-margin" => config.future_date_margin_days = next_value(&mut args, &arg)?,
+            "--null-future-delays" => config.null_future_delays = true,
+            "--fix-swapped-dates" => config.fix_swapped_dates = true,
+            "--keep-partial-rows" => config.keep_partial_rows = true,
+            "--strict" => config.strict = true,
+            "--large-file" => config.large_file = true,
+            "--no-color" => config.color = false,
+            "--page-size" => config.page_size = Some(next_value(&mut args, &arg)?),
+            "--by-year" => config.partition_by_year = true,
+            "--quiet" | "-q" => config.quiet = true,
+            "--version" | "-V" => config.show_version = true,
+            "--self-test" => config.self_test = Some(next_value(&mut args, &arg)?),
+            "--generate" => config.generate = true,
+            "--tui" => config.tui = true,
+            _ if arg.starts_with('-') && arg != STDIN_PATH => return Err(format!("Unknown argument: {}", arg).into()),
+            // Several datasets may be given as separate arguments or comma-separated
+            _ => cli_paths.extend(split_list(&arg)),
+        }
+    }
+    if !cli_paths.is_empty() {
+        config.file_paths = cli_paths;
+    }
+    if !cli_work_types.is_empty() {
+        config.work_types = cli_work_types;
+    }
+    if !cli_reports.is_empty() {
+        config.reports = cli_reports;
+    }
+    // Piped input with no path given is the dataset, e.g. `grep ... | mco2_rust --generate`
+    if config.generate && config.file_paths.is_empty() && !io::stdin().is_terminal() {
+        config.file_paths = vec![STDIN_PATH.to_string()];
+    }
+    if config.file_paths.iter().any(|path| path == STDIN_PATH) {
+        if !config.generate {
+            return Err("Reading the dataset from stdin (-) needs --generate, since the menu reads stdin".into());
+        }
+        if config.file_paths.len() > 1 {
+            return Err("The dataset from stdin (-) cannot be combined with other dataset files".into());
+        }
+    }
+    if config.page_size == Some(0) {
+        return Err("--page-size must be at least 1".into());
+    }
+    if config.recency_decay.is_some_and(|d| !(d > 0.0 && d <= 1.0)) {
+        return Err("--recency-decay must be above 0 and at most 1".into());
+    }
+    if config.savings_flag_low.partial_cmp(&config.savings_flag_high) != Some(Ordering::Less) {
+        return Err("--savings-flag-low must be below --savings-flag-high".into());
+    }
+    if config.min_total_cost.is_some_and(|floor| floor.is_nan() || floor < 0.0) {
+        return Err("--min-total-cost must not be negative".into());
+    }
+    if config.context_years < 0 {
+        return Err("--context-years must not be negative".into());
+    }
+    if config.limited_sample_min > config.adequate_sample_min {
+        return Err("--limited-sample must not be greater than --adequate-sample".into());
+    }
+    if config.delay_cap_percentile.is_some_and(|p| !(p > 0.0 && p <= 100.0)) {
+        return Err("--cap-delays must be a percentile above 0 and at most 100".into());
+    }
+    if let (Some(from), Some(to)) = (config.start_window.from, config.start_window.to)
+        && from > to
+    {
+        return Err("--start-date-from must not be after --start-date-to".into());
+    }
+    if config.generate && config.file_paths.is_empty() {
+        return Err("--generate requires a dataset path".into());
+    }
+    Ok(config)
+}
+
+const CONFIG_FILE: &str = "config.toml";
+const SAMPLE_CONFIG_FILE: &str = "config.sample.toml";
+
+/// Settings read from a TOML file. Every key is optional and named after its command-line
+/// flag; see `SAMPLE_CONFIG` for the schema.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    datasets: Option<Vec<String>>,
+    limit: Option<usize>,
+    quiet: Option<bool>,
+    min_projects: Option<usize>,
+    min_total_cost: Option<f64>,
+    min_region_sample: Option<usize>,
+    merge_regions_below: Option<usize>,
+    context_years: Option<i32>,
+    limited_sample: Option<usize>,
+    adequate_sample: Option<usize>,
+    near_miss_min: Option<usize>,
+    near_miss_max: Option<usize>,
+    island_correction: Option<bool>,
+    history: Option<bool>,
+    missing_delay: Option<String>,
+    overrun_margin: Option<String>,
+    cap_delays: Option<f64>,
+    format: Option<String>,
+    geojson: Option<bool>,
+    html: Option<bool>,
+    verify_output: Option<bool>,
+    rank_by: Option<String>,
+    rank_style: Option<String>,
+    precision: Option<usize>,
+    contractor_projects: Option<usize>,
+    reports: Option<Vec<String>>,
+    work_types: Option<Vec<String>>,
+    round_divisor: Option<f64>,
+    weighted_median: Option<bool>,
+    raw_scores: Option<bool>,
+    vs_average: Option<bool>,
+    recency_decay: Option<f64>,
+    savings_flag_high: Option<f64>,
+    savings_flag_low: Option<f64>,
+    compare_ranking: Option<String>,
+    population_file: Option<String>,
+    color: Option<bool>,
+    page_size: Option<usize>,
+    by_year: Option<bool>,
+    ongoing_threshold: Option<i64>,
+    dupe_threshold: Option<f64>,
+    at_budget_fraction: Option<f64>,
+    repeat_tolerance: Option<f64>,
+    start_date_from: Option<NaiveDate>,
+    start_date_to: Option<NaiveDate>,
+    outlier_metric: Option<String>,
+    min_kept_fraction: Option<f64>,
+    anonymize: Option<bool>,
+    future_date_margin: Option<i64>,
+    null_future_delays: Option<bool>,
+    fix_swapped_dates: Option<bool>,
+    keep_partial_rows: Option<bool>,
+    strict: Option<bool>,
+    large_file: Option<bool>,
+}
+
+impl ConfigFile {
+    fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        toml::from_str(&text).map_err(|e| format!("Invalid {}: {}", path, e).into())
+    }
+
+    fn apply(self, config: &mut Config) -> Result<(), Box<dyn Error>> {
+        fn parsed<T: std::str::FromStr<Err = String>>(value: Option<String>) -> Result<Option<T>, String> {
+            value.map(|v| v.parse()).transpose()
+        }
+
+        if let Some(v) = self.datasets { config.file_paths = v; }
+        if let Some(v) = self.limit { config.row_limit = Some(v); }
+        if let Some(v) = self.quiet { config.quiet = v; }
+        if let Some(v) = self.min_projects { config.min_projects = v; }
+        if let Some(v) = self.min_total_cost { config.min_total_cost = Some(v); }
+        if let Some(v) = self.min_region_sample { config.min_region_sample = v; }
+        if let Some(v) = self.context_years { config.context_years = v; }
+        if let Some(v) = self.merge_regions_below { config.merge_regions_below = Some(v); }
+        if let Some(v) = self.limited_sample { config.limited_sample_min = v; }
+        if let Some(v) = self.adequate_sample { config.adequate_sample_min = v; }
+        if let Some(v) = self.near_miss_min { config.near_miss_min = v; }
+        if let Some(v) = self.near_miss_max { config.near_miss_max = Some(v); }
+        if let Some(v) = self.island_correction { config.correct_islands = v; }
+        if let Some(v) = self.history { config.append_history = v; }
+        if let Some(v) = parsed(self.missing_delay)? { config.missing_delay = v; }
+        if let Some(v) = parsed(self.overrun_margin)? { config.overrun_margin = v; }
+        if let Some(v) = self.cap_delays { config.delay_cap_percentile = Some(v); }
+        if let Some(v) = parsed(self.format)? { config.table_format = v; }
+        if let Some(v) = self.geojson { config.geojson = v; }
+        if let Some(v) = self.html { config.html = v; }
+        if let Some(v) = self.verify_output { config.verify_output = v; }
+        if let Some(v) = parsed(self.rank_by)? { config.rank_by = v; }
+        if let Some(v) = parsed(self.rank_style)? { config.rank_style = v; }
+        if let Some(v) = self.precision { config.precision = Some(v); }
+        if let Some(v) = self.contractor_projects { config.contractor_projects = Some(v); }
+        if let Some(v) = self.reports {
+            config.reports = v.iter().map(|name| name.parse()).collect::<Result<_, _>>()?;
+        }
+        if let Some(v) = self.work_types { config.work_types = v; }
+        if let Some(v) = self.round_divisor { config.round_divisor = v; }
+        if let Some(v) = self.weighted_median { config.weighted_median = v; }
+        if let Some(v) = self.raw_scores { config.raw_scores = v; }
+        if let Some(v) = self.vs_average { config.compare_to_average = v; }
+        if let Some(v) = self.recency_decay { config.recency_decay = Some(v); }
+        if let Some(v) = self.savings_flag_high { config.savings_flag_high = v; }
+        if let Some(v) = self.savings_flag_low { config.savings_flag_low = v; }
+        if let Some(v) = self.compare_ranking { config.compare_ranking = Some(v); }
+        if let Some(v) = self.population_file { config.population_file = Some(v); }
+        if let Some(v) = self.color { config.color = v; }
+        if let Some(v) = self.page_size { config.page_size = Some(v); }
+        if let Some(v) = self.by_year { config.partition_by_year = v; }
+        if let Some(v) = self.ongoing_threshold { config.ongoing_threshold_days = v; }
+        if let Some(v) = self.dupe_threshold { config.dupe_threshold = v; }
+        if let Some(v) = self.at_budget_fraction { config.at_budget_fraction = v; }
+        if let Some(v) = self.repeat_tolerance { config.repeat_tolerance = v; }
+        if let Some(v) = self.start_date_from { config.start_window.from = Some(v); }
+        if let Some(v) = self.start_date_to { config.start_window.to = Some(v); }
+        if let Some(v) = parsed(self.outlier_metric)? { config.outlier_metric = v; }
+        if let Some(v) = self.min_kept_fraction { config.min_kept_fraction = Some(v); }
+        if let Some(v) = self.anonymize { config.anonymize = v; }
+        if let Some(v) = self.future_date_margin { config.future_date_margin_days = v; }
+        if let Some(v) = self.null_future_delays { config.null_future_delays = v; }
+        if let Some(v) = self.fix_swapped_dates { config.fix_swapped_dates = v; }
+        if let Some(v) = self.keep_partial_rows { config.keep_partial_rows = v; }
+        if let Some(v) = self.strict { config.strict = v; }
+        if let Some(v) = self.large_file { config.large_file = v; }
+        Ok(())
+    }
+}
+
+/// Written by `--write-sample-config`: every supported key, commented out at its default.
+const SAMPLE_CONFIG: &str = r#"# mco2_rust settings. Put this in config.toml next to where you run the program, or pass
+# --config PATH. Every key is optional; command-line flags override the file.
+
+# Dataset file(s) to load (default: ../dpwh_flood_control_projects.csv)
+# datasets = ["../dpwh_flood_control_projects.csv"]
+
+# Stop after this many data rows
+# limit = 1000
+
+# Print only one-line summaries instead of the report tables
+# quiet = false
+
+# Report 2: minimum projects for a contractor to be ranked, and the sort key
+# min_projects = 5
+# Also drop contractors whose total contract cost is below this (asked for in interactive
+# runs when not set here or with --min-total-cost)
+# min_total_cost = 0.0
+# rank_by = "cost"            # cost | reliability | savings
+# rank_style = "ordinal"      # ordinal (1,2,3,4) | competition (1,2,2,4) | dense (1,2,2,3)
+
+# Report 1: regions with fewer projects than this are marked with * and low_sample = true
+# min_region_sample = 3
+
+# Report 1: show regions with fewer projects than this as one combined "Other" row
+# (the export still lists every region)
+# merge_regions_below = 5
+
+# Also load this many funding years before 2021, used only as the previous year in the
+# Report 3 and regional budget YoY changes (never in the rows or totals)
+# context_years = 0
+
+# Report 3: sample_adequacy is "sparse" below limited_sample projects, "limited" below
+# adequate_sample, and "adequate" from there on
+# limited_sample = 3
+# adequate_sample = 10
+
+# Near-miss contractors: project-count band just below min_projects
+# near_miss_min = 2
+# near_miss_max = 4
+
+# Repair MainIsland labels using the region lookup
+# island_correction = true
+
+# Append each run's summary to summary_history.csv
+# history = false
+
+# How projects without a delay enter averages: "exclude" or "zero"
+# missing_delay = "exclude"
+
+# Minimum excess of cost over budget to count as an overrun: an amount ("50000") or percent ("2.5%")
+# overrun_margin = "0"
+
+# Cap delays at this percentile before averaging them in Reports 1 and 2 (median, high-delay % unaffected)
+# cap_delays = 99.0
+
+# Table file format: "csv", "tsv" or "both"
+# format = "csv"
+
+# Also write Report 1 as report1_regions.geojson for GIS tools. Geometries are null since the
+# data has no boundaries; join the features to a region layer on the "region" property
+# geojson = false
+
+# Also write Reports 1-3 as standalone, styled HTML pages (report1.html, report2.html, report3.html)
+# html = false
+
+# Read each written CSV/TSV table back into its row type and fail if it does not match
+# what was written (a check on the export path; costs one extra read per table)
+# verify_output = false
+
+# Decimal places for every numeric output column (default: per-column)
+# precision = 2
+
+# Write the projects behind the top N Report 2 contractors to report2_contractor_projects.json
+# contractor_projects = 15
+
+# Produce only these outputs: 1/regional, 2/contractors, 3/annual, tiers, region-yoy, reach, churn, summary
+# reports = ["1", "summary"]
+
+# Limit reports to these types of work (substring match); empty prompts interactively
+# work_types = ["Construction of Flood Mitigation Structure"]
+
+# Divisor for the round-number check
+# round_divisor = 1000000.0
+
+# Add a budget-weighted median savings column to Report 1
+# weighted_median = false
+
+# Add columns with the efficiency score before clamping to 0-100 and the reliability index
+# before capping at 100, to see how far past the limits a region or contractor goes
+# raw_scores = false
+
+# Add Report 2 columns comparing each contractor to the dataset average
+# vs_average = false
+
+# Add a Report 2 "current form" reliability index: each project is weighted by
+# recency_decay^(2023 - funding year), so 0.5 halves the weight of every year further back.
+# Must be above 0 and at most 1; 1 gives the unweighted index
+# recency_decay = 0.5
+
+# Report 2 Savings Flag: contractors whose savings, as a % of approved budget, are above
+# the high mark (possible padded budgets) or below the low mark (overruns)
+# savings_flag_high = 40.0
+# savings_flag_low = 0.0
+
+# Diff Report 2 against a previous run's ranking file (writes report2_ranking_changes.csv)
+# compare_ranking = "previous/report2_contractor_ranking.csv"
+
+# Add a Report 1 budget per capita from a CSV with region and population columns
+# population_file = "region_population.csv"
+
+# Write a separate set of reports for each funding year (report1_regional_summary_2021.csv, ...)
+# by_year = false
+
+# Colour Report 2 risk flags on a terminal (also off when NO_COLOR is set)
+# color = true
+
+# Pause the Report 1-3 tables every this many rows on a terminal; unset prints them in one go
+# page_size = 40
+
+# Days after which an ongoing project counts as overdue
+# ongoing_threshold = 365
+
+# Minimum similarity (0-1) for the near-duplicate contractor name check
+# dupe_threshold = 0.9
+
+# Flag contractors with more than this share (0-1) of projects bid exactly at budget
+# at_budget_fraction = 0.5
+
+# Suspected repeat contracts: same contractor, region and type of work, with budgets within
+# this fraction (0-1) of the smallest in the cluster
+# repeat_tolerance = 0.02
+
+# Value checked against the 1.5 x IQR fences by the outlier-trimmed rerun: cost | savings | delay
+# outlier_metric = "cost"
+
+# Exit with an error when fewer than this fraction of rows are kept
+# min_kept_fraction = 0.5
+
+# Replace contractor names with pseudonyms
+# anonymize = false
+
+# Completion dates more than this many days in the future are flagged
+# future_date_margin = 30
+
+# Discard the delay of projects with a flagged future completion date
+# null_future_delays = false
+
+# Treat a completion date before the start date as swapped columns and swap them back
+# fix_swapped_dates = false
+
+# Keep only projects whose StartDate falls in this window (inclusive, YYYY-MM-DD); rows
+# without a start date are dropped while either bound is set
+# start_date_from = "2022-07-01"
+# start_date_to = "2023-06-30"
+
+# Drop rows only when FundingYear, ApprovedBudgetForContract or ContractCost is blank
+# keep_partial_rows = false
+
+# Stop the load with an error at the first row that fails to parse or has the wrong number
+# of fields, instead of skipping it (useful when onboarding a new file)
+# strict = false
+
+# Aggregate while streaming instead of keeping every project in memory
+# large_file = false
+"#;
+
+fn next_value<T: std::str::FromStr, I: Iterator<Item = String>>(
+    args: &mut I,
+    flag: &str,
+) -> Result<T, Box<dyn Error>> {
+    let value = args.next().ok_or(format!("{} requires a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid {} value: {}", flag, value).into())
+}
+
+/// Splits a comma-separated list, dropping empty entries.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 fn print_menu() {
     println!("\n=== DPWH Flood Control Data Analysis Pipeline ===");
     println!("===               By Rintaro Iwata            ===\n");
     println!("[1] Load Dataset (Filter 2021-2023)");
     println!("[2] Generate Reports");
-    println!("[3] Exit");
+    println!("[3] Validate Dataset");
+    println!("[4] Check Round-Number Amounts");
+    println!("[5] Ongoing Projects Report");
+    println!("[6] Dry-Run Load (Row Counts Only)");
+    println!("[7] Export Budget vs. Cost Scatter Data");
+    println!("[8] Find Near-Duplicate Contractor Names");
+    println!("[9] Regenerate Reports With Outliers Trimmed");
+    println!("[10] Find Contractors Bidding at Budget");
+    println!("[11] Find Suspected Repeat Contracts");
+    println!("[12] Quick Summary (summary.json Only)");
+    println!("[13] Explore Reports (Sortable Table)");
+    println!("[14] Exit");
     print!("Enter choice: ");
     io::stdout().flush().unwrap_or_default();
 }
 
-fn get_menu_choice() -> Result<i32, Box<dyn Error>> {
-    let mut choice_str = String::new();
-    io::stdin().read_line(&mut choice_str)?;
-    let choice: i32 = match choice_str.trim().parse() {
-        Ok(num) => num,
-        Err(_) => 0,
-    };
-    Ok(choice)
+/// Menu keywords accepted in place of the option numbers.
+const MENU_KEYWORDS: [(&str, i32); 16] = [
+    ("load", 1),
+    ("report", 2),
+    ("reports", 2),
+    ("validate", 3),
+    ("round", 4),
+    ("ongoing", 5),
+    ("dry-run", 6),
+    ("scatter", 7),
+    ("dupes", 8),
+    ("trim", 9),
+    ("at-budget", 10),
+    ("repeats", 11),
+    ("summary", 12),
+    ("explore", 13),
+    ("exit", 14),
+    ("quit", 14),
+];
+const MENU_OPTION_COUNT: i32 = 14;
+
+/// Reads menu input until it is valid, re-prompting with the reason otherwise.
+/// Returns `None` when stdin reaches end of input.
+fn get_menu_choice() -> Result<Option<i32>, Box<dyn Error>> {
+    loop {
+        let mut choice_str = String::new();
+        if io::stdin().read_line(&mut choice_str)? == 0 {
+            return Ok(None);
+        }
+        match parse_menu_choice(&choice_str) {
+            Ok(choice) => return Ok(Some(choice)),
+            Err(message) => {
+                print!("{}\nEnter choice: ", message);
+                io::stdout().flush()?;
+            }
+        }
+    }
+}
+
+/// Parses a menu number or keyword, case-insensitively and ignoring surrounding whitespace.
+fn parse_menu_choice(input: &str) -> Result<i32, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(format!(
+            "No choice entered. Type a number from 1 to {} or a keyword such as \"load\".",
+            MENU_OPTION_COUNT
+        ));
+    }
+    if let Ok(number) = input.parse::<i64>() {
+        return if (1..=MENU_OPTION_COUNT as i64).contains(&number) {
+            Ok(number as i32)
+        } else {
+            Err(format!("{} is not a menu option; choose 1 to {}.", number, MENU_OPTION_COUNT))
+        };
+    }
+    let keyword = input.to_lowercase();
+    MENU_KEYWORDS
+        .iter()
+        .find(|(name, _)| *name == keyword)
+        .map(|(_, choice)| *choice)
+        .ok_or_else(|| {
+            let names: Vec<&str> = MENU_KEYWORDS.iter().map(|(name, _)| *name).collect();
+            format!("Unrecognized choice \"{}\". Use 1-{} or one of: {}.", input, MENU_OPTION_COUNT, names.join(", "))
+        })
 }
 
-fn handle_load_data(projects: &mut Vec<Project>, data_loaded: &mut bool) -> bool {
-    println!("Processing dataset...");
-    let file_path = "../dpwh_flood_control_projects.csv";
-    match load_data(file_path) {
-        Ok((record_count, loaded_projects)) => {
+fn prompt_line(prompt: &str) -> io::Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Asks which dataset files to load; pressing Enter keeps the paths from the command line.
+fn prompt_dataset_paths(config: &Config) -> io::Result<Vec<String>> {
+    let defaults = config.dataset_paths();
+    let input = prompt_line(&format!(
+        "Dataset path(s), comma-separated [Enter for {}]: ",
+        defaults.join(", ")
+    ))?;
+    let paths = split_list(&input);
+    Ok(if paths.is_empty() { defaults } else { paths })
+}
+
+/// Loads the dataset into `projects`. Returns an error only when the kept-row fraction
+/// falls below `config.min_kept_fraction`, which ends the program with a non-zero exit.
+fn handle_load_data(
+    config: &Config,
+    paths: &[String],
+    projects: &mut Vec<Project>,
+    context_projects: &mut Vec<Project>,
+    streamed_reports: &mut Option<Reports>,
+    dataset_sha256: &mut String,
+    data_loaded: &mut bool,
+) -> Result<bool, Box<dyn Error>> {
+    if !config.quiet {
+        println!("Processing dataset...");
+    }
+    let _step = InterruptibleStep::begin();
+    let result = if config.large_file {
+        stream_reports(paths, config).map(|(summary, reports)| {
+            projects.clear();
+            context_projects.clear();
+            *streamed_reports = Some(reports);
+            summary
+        })
+    } else {
+        load_data(paths, config).map(|(mut summary, loaded_projects)| {
             *projects = loaded_projects;
+            *context_projects = std::mem::take(&mut summary.context_projects);
+            *streamed_reports = None;
+            summary
+        })
+    };
+    if let Ok(summary) = &result
+        && summary.interrupted
+    {
+        projects.clear();
+        context_projects.clear();
+        *streamed_reports = None;
+        *data_loaded = false;
+        println!(
+            "WARNING: Load interrupted after {} rows ({} kept); partial data discarded.",
+            summary.record_count, summary.kept_count
+        );
+        return Ok(true);
+    }
+    match result {
+        Ok(summary) => {
             *data_loaded = true;
+            dataset_sha256.clone_from(&summary.dataset_sha256);
+            let limit_note = match config.row_limit {
+                Some(limit) => format!(" (limit of {} rows applied)", limit),
+                None => String::new(),
+            };
             println!(
-                "SUCCESS: {} rows loaded, {} rows filtered for 2021-2023",
-                record_count,
-                projects.len()
+                "SUCCESS: {} rows loaded{}, {} rows filtered for 2021-2023",
+                summary.record_count,
+                limit_note,
+                summary.kept_count
             );
+            if summary.files.len() > 1 {
+                for file in &summary.files {
+                    println!("  {}: {} rows read, {} kept", file.path, file.record_count, file.kept_count);
+                }
+                if summary.duplicates_skipped > 0 {
+                    println!(
+                        "  {} rows skipped as duplicates of a ContractId from an earlier file",
+                        summary.duplicates_skipped
+                    );
+                }
+            }
+            if config.context_years > 0 {
+                println!(
+                    "Kept {} rows from {} as YoY context only; they are not in the counts or totals.",
+                    summary.context_rows,
+                    config.context_years_label()
+                );
+            }
+            if !config.quiet {
+                println!(
+                    "Dataset SHA-256: {} (mco2_rust {})",
+                    summary.dataset_sha256,
+                    env!("CARGO_PKG_VERSION")
+                );
+            }
+            if summary.implausible_year_rows > 0 {
+                println!("{:!<72}", "");
+                println!(
+                    "WARNING: {} rows have a FundingYear outside {}-{} (e.g. \"{}\").",
+                    summary.implausible_year_rows,
+                    PLAUSIBLE_FUNDING_YEARS.start(),
+                    PLAUSIBLE_FUNDING_YEARS.end(),
+                    summary.implausible_year_example.as_deref().unwrap_or_default()
+                );
+                println!("This usually means the columns are shifted; check the header against the expected layout.");
+                println!("{:!<72}", "");
+            }
+            if summary.malformed_rows > 0 {
+                println!(
+                    "WARNING: {} rows had a different number of fields than the header and were skipped (see log for details).",
+                    summary.malformed_rows
+                );
+            }
+            if config.large_file && !config.quiet {
+                println!("Large-file mode: reports aggregated while streaming; projects were not retained.");
+            }
+            if summary.kept_count == 0 {
+                println!("WARNING: No projects matched the filters; reports will be empty.");
+                println!("Check that the file has data rows and that the filters are not too narrow.");
+            }
+            if summary.future_end_dates > 0 {
+                println!(
+                    "WARNING: {} rows have completion dates more than {} days in the future{}.",
+                    summary.future_end_dates,
+                    config.future_date_margin_days,
+                    if config.null_future_delays { "; their delays were discarded" } else { "" }
+                );
+            }
+            if config.keep_partial_rows {
+                println!(
+                    "Kept {} rows with blank non-critical fields that the strict blank filter would have dropped.",
+                    summary.partial_rows_kept
+                );
+            }
+            if config.correct_islands && !config.quiet {
+                println!(
+                    "Corrected main island on {} rows using the region lookup.",
+                    summary.islands_corrected
+                );
+            }
+            if !config.quiet {
+                let delays = &summary.delays;
+                println!(
+                    "Delays: {} late, {} on time (0 days), {} early, {} missing. {}.",
+                    delays.late,
+                    delays.on_time,
+                    delays.early,
+                    delays.missing,
+                    config.missing_delay.label()
+                );
+            }
+            if config.start_window.is_active() {
+                println!(
+                    "Excluded {} rows from 2021-2023 outside the window ({}) or without a start date.",
+                    summary.outside_start_window_rows,
+                    config.start_window.label()
+                );
+            }
+            if config.fix_swapped_dates {
+                println!(
+                    "Swapped start and completion dates on {} rows where completion came first.",
+                    summary.dates_swapped
+                );
+            }
+
+            if config.anonymize && config.large_file {
+                println!("WARNING: --anonymize is not supported in large-file mode; names were left as-is.");
+            } else if config.anonymize {
+                let key = anonymize_contractors(projects, config);
+                match write_csv(&key, "contractor_key.csv") {
+                    Ok(()) => println!(
+                        "Contractor names replaced with pseudonyms; key written to contractor_key.csv (internal use only)."
+                    ),
+                    Err(e) => println!("ERROR: Failed to write contractor_key.csv: {}", e),
+                }
+            }
+
+            if let Some(min_fraction) = config.min_kept_fraction {
+                let kept_fraction = if summary.record_count > 0 {
+                    summary.kept_count as f64 / summary.record_count as f64
+                } else {
+                    0.0
+                };
+                if kept_fraction < min_fraction {
+                    return Err(format!(
+                        "Data quality check failed: kept {} of {} rows ({:.1}%), below the required {:.1}%",
+                        summary.kept_count,
+                        summary.record_count,
+                        kept_fraction * 100.0,
+                        min_fraction * 100.0
+                    )
+                    .into());
+                }
+            }
         }
         Err(e) => println!("ERROR: Failed to load data: {}", e),
     }
-    true
+    Ok(true)
+}
+
+/// Writes only summary.json and prints its figures, skipping the report tables; the
+/// same as `--reports summary`.
+fn handle_summary_only(
+    config: &Config,
+    projects: &[Project],
+    context_projects: &[Project],
+    streamed_reports: Option<&Reports>,
+    dataset_sha256: &str,
+    data_loaded: bool,
+) -> bool {
+    let config = Config { reports: vec![ReportKind::Summary], ..config.clone() };
+    handle_generate_reports(&config, projects, context_projects, streamed_reports, dataset_sha256, data_loaded)
 }
 
-fn handle_generate_reports(projects: &[Project], data_loaded: bool) -> bool {
+/// Generates the reports as option 2 does, then opens them in the interactive explorer; the
+/// same as `--tui`.
+fn handle_explore_reports(
+    config: &Config,
+    projects: &[Project],
+    context_projects: &[Project],
+    streamed_reports: Option<&Reports>,
+    dataset_sha256: &str,
+    data_loaded: bool,
+) -> bool {
+    let config = Config { tui: true, ..config.clone() };
+    handle_generate_reports(&config, projects, context_projects, streamed_reports, dataset_sha256, data_loaded)
+}
+
+fn handle_generate_reports(
+    config: &Config,
+    projects: &[Project],
+    context_projects: &[Project],
+    streamed_reports: Option<&Reports>,
+    dataset_sha256: &str,
+    data_loaded: bool,
+) -> bool {
     if !data_loaded {
         println!("WARNING: Please load the dataset first [Option 1].");
         return true;
     }
-    println!("Generating reports...");
-
-    match generate_reports(projects) {
-        Ok((report1, report2, report3)) => {
-            display_report_1(&report1);
-            display_report_2(&report2);
-            display_report_3(&report3);
+    if let Some(reports) = streamed_reports {
+        if reports.summary.total_projects_analyzed == 0 {
+            println!("WARNING: No projects matched the filters; report generation skipped.");
+            return true;
+        }
+        let _step = InterruptibleStep::begin();
+        if config.contractor_projects.is_some() {
+            println!("WARNING: --contractor-projects needs per-project data; skipped in large-file mode.");
+        }
+        if config.delay_cap_percentile.is_some() {
+            println!("WARNING: --cap-delays needs every delay up front; skipped in large-file mode.");
+        }
+        if config.recency_decay.is_some() {
+            println!("WARNING: --recency-decay needs per-project data; skipped in large-file mode.");
+        }
+        if config.merge_regions_below.is_some() {
+            println!("WARNING: --merge-regions-below needs per-project data; every region listed in large-file mode.");
+        }
+        if config.partition_by_year {
+            println!("WARNING: --by-year needs per-project data; combined reports written in large-file mode.");
+        }
+        // Streamed rows have no recency-weighted index, so leave its column out of the tables
+        let config = &Config { recency_decay: None, ..config.clone() };
+        match write_reports(reports, None, config) {
+            Ok(written_files) => {
+                show_reports(reports, &written_files, config);
+                if config.tui {
+                    drop(_step);
+                    explore_reports(reports);
+                }
+            }
+            Err(e) => print_report_error(e),
+        }
+        return true;
+    }
+    if projects.is_empty() {
+        println!("WARNING: No projects matched the filters; reports will be empty.");
+        println!("Report generation skipped. Check the dataset file or loosen the filters.");
+        return true;
+    }
 
-            println!("\nSUCCESS: Reports saved to CSV files and summary.json created.");
+    // Pick the type-of-work filter: from --work-type, or prompted in interactive runs
+    let mut config = config.clone();
+    if config.work_types.is_empty() && !config.generate {
+        let work_types = distinct_work_types(projects);
+        println!("\nAvailable types of work:");
+        for (i, work_type) in work_types.iter().enumerate() {
+            println!("  [{}] {}", i + 1, work_type);
         }
-        Err(e) => {
-            println!("ERROR: Failed to generate reports: {}", e);
+        match prompt_line("Filter by type of work (numbers or names, comma-separated; blank for all): ") {
+            Ok(input) => config.work_types = resolve_work_type_selection(&input, &work_types),
+            Err(e) => {
+                println!("ERROR: Failed to read selection: {}", e);
+                return true;
+            }
+        }
+    }
+    // Report 2 total-cost floor: from --min-total-cost, or prompted in interactive runs
+    if config.min_total_cost.is_none() && !config.generate && config.wants(ReportKind::Contractors) {
+        match prompt_line("Minimum total contract cost for Report 2 [Enter for 0]: ") {
+            Ok(input) if input.trim().is_empty() => config.min_total_cost = Some(0.0),
+            Ok(input) => match input.trim().replace(',', "").parse::<f64>() {
+                Ok(floor) if floor >= 0.0 => config.min_total_cost = Some(floor),
+                _ => {
+                    println!("ERROR: \"{}\" is not a non-negative amount.", input.trim());
+                    return true;
+                }
+            },
+            Err(e) => {
+                println!("ERROR: Failed to read the cost floor: {}", e);
+                return true;
+            }
         }
     }
+
+    let filtered_projects;
+    let projects = if config.work_types.is_empty() {
+        projects
+    } else {
+        filtered_projects = filter_by_work_type(projects, &config.work_types);
+        println!(
+            "Type-of-work filter matched {} of {} projects.",
+            filtered_projects.len(),
+            projects.len()
+        );
+        &filtered_projects[..]
+    };
+    if projects.is_empty() {
+        println!("WARNING: No projects matched the type-of-work filter; report generation skipped.");
+        return true;
+    }
+    if let Some(percentile) = config.delay_cap_percentile {
+        let mut delays = collect_delays(projects, config.missing_delay);
+        config.delay_cap = percentile_value(&mut delays, percentile);
+    }
+    // Context projects get the same type-of-work filter, so baselines compare like with like
+    config.yoy_baselines = if config.work_types.is_empty() {
+        YoyBaselines::from_projects(context_projects)
+    } else {
+        YoyBaselines::from_projects(&filter_by_work_type(context_projects, &config.work_types))
+    };
+    let config = &config;
+    if config.partition_by_year {
+        generate_reports_by_year(projects, dataset_sha256, config);
+        return true;
+    }
+
+    if !config.quiet {
+        println!("Generating reports...");
+    }
+
+    let _step = InterruptibleStep::begin();
+    match generate_reports(projects, dataset_sha256, config) {
+        Ok((reports, written_files)) => {
+            show_reports(&reports, &written_files, config);
+            if config.tui {
+                drop(_step);
+                explore_reports(&reports);
+            } else if !config.generate && !config.quiet && !reports.report1.is_empty() {
+                drop(_step);
+                drill_down_report_1(&reports.report1, projects, config);
+            }
+        }
+        Err(e) => print_report_error(e),
+    }
     true
 }
 
-fn handle_exit() -> bool {
-    println!("Exiting application.");
-    false
+/// Opens the computed reports in the interactive explorer, which needs a real terminal.
+fn explore_reports(reports: &Reports) {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        println!("WARNING: The report explorer needs an interactive terminal; skipped.");
+        return;
+    }
+    if let Err(e) = reports.tables().and_then(|tables| Ok(explorer::explore(tables)?)) {
+        println!("ERROR: Report explorer failed: {}", e);
+    }
 }
 
-fn handle_invalid_choice() -> bool {
-    println!("Invalid choice, please try again.");
-    true
+/// --by-year: runs every report once per funding year over that year's projects, writing
+/// year-suffixed files, then checks that the years together cover the whole selection.
+fn generate_reports_by_year(projects: &[Project], dataset_sha256: &str, config: &Config) {
+    let mut by_year: BTreeMap<i32, Vec<Project>> = BTreeMap::new();
+    for p in projects {
+        by_year.entry(p.funding_year).or_default().push(p.clone());
+    }
+
+    let _step = InterruptibleStep::begin();
+    let mut partitioned = 0;
+    for (&year, year_projects) in &by_year {
+        println!("\n{:=<60}", "");
+        println!("Funding year {} ({} projects)", year, year_projects.len());
+        println!("{:=<60}", "");
+        let year_config = Config { partition_year: Some(year), ..config.clone() };
+        match generate_reports(year_projects, dataset_sha256, &year_config) {
+            Ok((reports, written_files)) => {
+                show_reports(&reports, &written_files, &year_config);
+                partitioned += reports.summary.total_projects_analyzed;
+            }
+            Err(e) => {
+                print_report_error(e);
+                return;
+            }
+        }
+    }
+    if partitioned == projects.len() {
+        println!("\nSUCCESS: {} funding years together cover all {} projects.", by_year.len(), projects.len());
+    } else {
+        println!("\nWARNING: The funding years cover {} of {} projects.", partitioned, projects.len());
+    }
+}
+
+/// Lets the user pick a Report 1 row and lists the projects behind it, with the working for
+/// each figure. Re-groups on demand rather than keeping the groups around.
+fn drill_down_report_1(report: &[InfrastructureTrends], projects: &[Project], config: &Config) {
+    loop {
+        let input = match prompt_line("\nExplain a Report 1 row (row number or Region[/Island]; blank to return): ") {
+            Ok(input) if !input.is_empty() => input,
+            _ => return,
+        };
+        let row = match input.parse::<usize>() {
+            Ok(n) => report.get(n.wrapping_sub(1)),
+            Err(_) => {
+                let (region, island) = match input.split_once('/') {
+                    Some((region, island)) => (region.trim(), Some(island.trim())),
+                    None => (input.as_str(), None),
+                };
+                report.iter().find(|r| {
+                    r.region.eq_ignore_ascii_case(region)
+                        && island.is_none_or(|island| r.main_island.eq_ignore_ascii_case(island))
+                })
+            }
+        };
+        match row {
+            Some(row) => explain_report_1_row(row, projects, config),
+            None => println!("No Report 1 row matches \"{}\".", input),
+        }
+    }
 }
 
-fn display_report_1(report: &[InfrastructureTrends]) {
+fn explain_report_1_row(row: &InfrastructureTrends, projects: &[Project], config: &Config) {
+    let mut group: Vec<&Project> = projects
+        .iter()
+        .filter(|p| p.region == row.region && p.main_island == row.main_island)
+        .collect();
+    group.sort_by(|a, b| a.cost_savings.partial_cmp(&b.cost_savings).unwrap_or(Ordering::Equal));
+
     println!("\n{:-<130}", "");
-    println!("Report 1: Regional Flood Mitigation Efficiency Summary");
-    println!("(Filtered: 2021-2023 Projects)");
+    println!("{} / {}: {} projects (sorted by savings)", row.region, row.main_island, group.len());
     println!("{:-<130}", "");
     println!(
-        "{:<20} | {:<15} | {:>18} | {:>18} | {:>12} | {:>12} | {:>12}",
-        "Region",
-        "Main Island",
-        "Total Budget",
-        "Median Savings",
-        "Avg Delay",
-        "High Delay %",
-        "Efficiency"
+        "{:<16} | {:<40} | {:>6} | {:>18} | {:>18} | {:>18} | {:>8}",
+        "Project ID", "Contractor", "Year", "Approved Budget", "Contract Cost", "Savings", "Delay"
     );
     println!("{:-<130}", "");
-
-    for r in report {
-        let region = if r.region.len() > 18 {
-            format!("{}..", &r.region[..18])
-        } else {
-            r.region.clone()
-        };
-        let island = if r.main_island.len() > 13 {
-            format!("{}..", &r.main_island[..13])
-        } else {
-            r.main_island.clone()
-        };
-
+    for p in &group {
+        let contractor_name = truncate(&p.contractor, 38);
+        let delay = p.completion_delay_days.map_or("-".to_string(), |d| d.to_string());
         println!(
-            "{:<20} | {:<15} | {:>18.2} | {:>18.2} | {:>12.1} | {:>12.2}% | {:>12.2}",
-            region,
-            island,
-            r.total_budget,
-            r.median_savings,
-            r.avg_delay,
-            r.high_delay_pct,
-            r.efficiency_score
+            "{:<16} | {:<40} | {:>6} | {:>18} | {:>18} | {:>18} | {:>8}",
+            p.project_id,
+            contractor_name,
+            p.funding_year,
+            fmt_peso(p.approved_budget),
+            fmt_peso(p.contract_cost),
+            fmt_peso(p.cost_savings),
+            delay
         );
     }
     println!("{:-<130}", "");
-    println!("Table exported to report1_regional_summary.csv");
-}
 
-fn display_report_2(report: &[FinancialEfficiencies]) {
-    println!("\n{:-<140}", "");
-    println!("Report 2: Top Contractors Performance Ranking");
-    println!("(Top 15 by Total Contract Cost, >=5 Projects)");
-    println!("{:-<140}", "");
+    let pct = decimals(PERCENT_DECIMALS);
+    let mid = group.len() / 2;
+    if group.len().is_multiple_of(2) {
+        println!(
+            "Median savings: mean of the middle two savings ({} and {}) = {}",
+            fmt_peso(group[mid - 1].cost_savings),
+            fmt_peso(group[mid].cost_savings),
+            fmt_peso(row.median_savings)
+        );
+    } else {
+        println!("Median savings: middle savings value = {}", fmt_peso(row.median_savings));
+    }
+    let delays = collect_delays(group.iter().copied(), config.missing_delay);
+    let cap = config.delay_cap.unwrap_or(i64::MAX);
     println!(
-        "{:<5} | {:<40} | {:>18} | {:>10} | {:>12} | {:>18} | {:>12} | {:<10}",
-        "Rank",
-        "Contractor",
-        "Total Cost",
-        "Projects",
-        "Avg Delay",
-        "Total Savings",
-        "Reliability",
-        "Risk Flag"
+        "Avg delay: {} days over {} projects with a delay ({} of {} without one; {}) = {}",
+        delays.iter().map(|&d| d.min(cap)).sum::<i64>(),
+        delays.len(),
+        group.iter().filter(|p| p.completion_delay_days.is_none()).count(),
+        group.len(),
+        config.missing_delay.label(),
+        fmt_days(row.avg_delay)
     );
-    println!("{:-<140}", "");
-    
-    // Only print the top 15, matching the CSV output
-    for r in report.iter().take(15) {
-        let contractor_name = if r.contractor.len() > 38 {
-            format!("{}..", &r.contractor[..38])
-        } else {
-            r.contractor.clone()
-        };
-
+    if let Some(cap) = config.delay_cap {
+        println!("  ({} delays above the {}-day cap counted as {} days)", delays.iter().filter(|&&d| d > cap).count(), cap, cap);
+    }
+    println!(
+        "High delay: {} of {} delays over 30 days = {:.pct$}%",
+        delays.iter().filter(|&&d| d > 30).count(),
+        delays.len(),
+        row.high_delay_pct
+    );
+    println!(
+        "{}: {} of {} projects = {:.pct$}%",
+        config.overrun_margin.label(),
+        group.iter().filter(|p| config.overrun_margin.is_overrun(p)).count(),
+        group.len(),
+        row.overrun_rate
+    );
+    let score = decimals(SCORE_DECIMALS);
+    // --raw-scores shows the unclamped value as its own column, so show both steps
+    if config.raw_scores
+        && let Some(raw) = row.efficiency_raw
+    {
+        println!("Raw efficiency: median savings / avg delay x 100 = {:.score$}", raw);
+        println!("Efficiency: raw efficiency clamped to 0-100 = {:.score$}", row.efficiency_score);
+    } else {
         println!(
-            "{:<5} | {:<40} | {:>18.2} | {:>10} | {:>12.1} | {:>18.2} | {:>12.2} | {:<10}",
-            r.rank,
-            contractor_name,
-            r.total_cost,
-            r.num_projects,
-            r.avg_delay,
-            r.total_savings,
-            r.reliability_index,
-            r.risk_flag
+            "Efficiency: median savings / avg delay x 100, clamped to 0-100 = {:.score$}",
+            row.efficiency_score
         );
     }
-    println!("{:-<140}", "");
-    println!("Table exported to report2_contractor_ranking.csv");
 }
 
-fn display_report_3(report: &[PerformanceMetrics]) {
-    println!("\n{:-<120}", "");
-    println!("Report 3: Annual Project Type Cost Overrun Trends");
-    println!("(Grouped by FundingYear and TypeOfWork)");
-    println!("{:-<120}", "");
+/// Drops the projects outside the IQR fences of `config.outlier_metric` and reruns the reports
+/// on the rest, writing `trimmed_*` files next to the originals. The loaded data is unchanged.
+fn handle_trimmed_reports(
+    config: &Config,
+    projects: &[Project],
+    context_projects: &[Project],
+    dataset_sha256: &str,
+    data_loaded: bool,
+) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+    let Some((low, high)) = iqr_fences(projects, config.outlier_metric) else {
+        println!("WARNING: Too few projects with a {} to find outliers.", config.outlier_metric.label());
+        return true;
+    };
+    let (outliers, kept): (Vec<&Project>, Vec<&Project>) = projects.iter().partition(|p| {
+        config
+            .outlier_metric
+            .value(p)
+            .is_some_and(|v| v < low || v > high)
+    });
+    let show = |v: f64| match config.outlier_metric {
+        OutlierMetric::Delay => format!("{:.1} days", v),
+        _ => fmt_peso(v),
+    };
     println!(
-        "{:<6} | {:<45} | {:>10} | {:>18} | {:>12} | {:>12}",
-        "Year",
-        "Type of Work",
-        "Projects",
-        "Avg Savings",
-        "Overrun %",
-        "YoY Change %"
+        "Found {} of {} projects with {} outside {} .. {}.",
+        outliers.len(),
+        projects.len(),
+        config.outlier_metric.label(),
+        show(low),
+        show(high)
     );
-    println!("{:-<120}", "");
-    
-    for r in report {
-        let type_of_work = if r.type_of_work.len() > 43 {
-            format!("{}..", &r.type_of_work[..43])
-        } else {
-            r.type_of_work.clone()
-        };
+    match write_csv(&outliers, "outlier_projects.csv") {
+        Ok(()) => println!("SUCCESS: Outliers listed in outlier_projects.csv"),
+        Err(e) => println!("ERROR: Failed to write outlier_projects.csv: {}", e),
+    }
+    if outliers.is_empty() {
+        println!("Nothing to trim; reports were not regenerated.");
+        return true;
+    }
 
-        println!(
-            "{:<6} | {:<45} | {:>10} | {:>18.2} | {:>12.2}% | {:>12.2}%",
-            r.funding_year,
-            type_of_work,
-            r.total_projects,
-            r.avg_savings,
-            r.overrun_rate,
-            r.yoy_change
-        );
+    let trimmed: Vec<Project> = kept.into_iter().cloned().collect();
+    let config = Config { outliers_trimmed: true, ..config.clone() };
+    println!("Regenerating reports (outliers trimmed); the original report files are left as they are.");
+    handle_generate_reports(&config, &trimmed, context_projects, None, dataset_sha256, data_loaded)
+}
+
+fn print_report_error(e: Box<dyn Error>) {
+    if was_interrupted() {
+        println!("WARNING: Report generation interrupted; existing output files were left unchanged.");
+    } else {
+        println!("ERROR: Failed to generate reports: {}", e);
     }
-    println!("{:-<120}", "");
-    println!("Table exported to report3_annual_trends.csv");
-}
-
-fn parse_data(record: &StringRecord) -> Result<Option<Project>, Box<dyn Error>> {
-    let date_format = "%Y-%m-%d";
-
-    // REQ-0003: Filter for "Blank Values"
-    if record.iter().any(|f| f.trim().is_empty()) {
-        return Ok(None); // Skip row if any field is blank
-    }
-
-    // Parse funding_year (col 9)
-    let funding_year: i32 = record
-        .get(9)
-        .ok_or("Missing funding_year at col 9")?
-        .trim()
-        .parse()?;
-
-    // REQ-0003: Filter for 2021-2023
-    if !(2021..=2023).contains(&funding_year) {
-        return Ok(None); // Skip row if not in year range
-    }
-
-    // Parse Financials (removing commas)
-    let approved_budget: f64 = record
-        .get(11)
-        .ok_or("Missing approved_budget at col 11")?
-        .trim()
-        .replace(',', "")
-        .parse()?;
-
-    let contract_cost: f64 = record
-        .get(12)
-        .ok_or("Missing contract_cost at col 12")?
-        .trim()
-        .replace(',', "")
-        .parse()?;
-
-    // REQ-0004: Compute Derived Fields
-    let cost_savings = approved_budget - contract_cost;
-
-    // Dates
-    let start_str = record.get(16).unwrap_or("").trim();
-    let end_str = record.get(13).unwrap_or("").trim();
-    let start_date = NaiveDate::parse_from_str(start_str, date_format).ok();
-    let end_date = NaiveDate::parse_from_str(end_str, date_format).ok();
-    let completion_delay_days = match (start_date, end_date) {
-        (Some(s), Some(e)) => Some((e - s).num_days()),
-        _ => None,
-    };
+}
 
-    let project = Project {
-        main_island: record.get(0).unwrap_or("").trim().to_string(),
-        region: record.get(1).unwrap_or("").trim().to_string(),
-        type_of_work: record.get(8).unwrap_or("").trim().to_string(),
-        contractor: record.get(14).unwrap_or("").trim().to_string(),
-        funding_year,
-        approved_budget,
-        contract_cost,
-        cost_savings,
-        completion_delay_days,
-    };
+fn show_reports(reports: &Reports, written_files: &[String], config: &Config) {
+    if config.quiet {
+        println!("SUCCESS: Wrote {}", written_files.join(", "));
+        print_non_finite_note(reports);
+        return;
+    }
+    if let Err(e) = write_text_report(&mut io::stdout().lock(), reports, config, true) {
+        println!("ERROR: Failed to display reports: {}", e);
+    }
 
-    Ok(Some(project))
+    if config.reports.is_empty() && !config.is_derived_run() {
+        println!(
+            "\nSUCCESS: Reports saved to {} files, report.txt and summary.json created.",
+            config.table_format.label()
+        );
+    } else {
+        println!("\nSUCCESS: Wrote {}", written_files.join(", "));
+    }
+    if config.append_history && config.wants(ReportKind::Summary) && !config.is_derived_run() {
+        println!("Run appended to {}.", SUMMARY_HISTORY_FILE);
+    }
+    print_non_finite_note(reports);
 }
 
-fn load_data(file_path: &str) -> Result<(i32, Vec<Project>), Box<dyn Error>> {
-    let file = File::open(file_path)?;
-    let mut reader = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_reader(file);
-    let mut projects: Vec<Project> = Vec::new();
-    let mut record_count = 0;
-    let mut skipped_count = 0;
+fn print_non_finite_note(reports: &Reports) {
+    if reports.non_finite_replaced > 0 {
+        println!(
+            "WARNING: {} NaN/infinite metric values were replaced with 0.0.",
+            reports.non_finite_replaced
+        );
+    }
+}
 
-    for result in reader.records() {
-        let record = result?;
-        record_count += 1;
+fn handle_validate_dataset(config: &Config) -> bool {
+    println!("Validating dataset...");
+    for path in config.dataset_paths() {
+        if config.file_paths.len() > 1 {
+            println!("{}:", path);
+        }
+        validate_one_dataset(&path);
+    }
+    true
+}
 
-        match parse_data(&record) {
-            Ok(Some(project)) => {
-                projects.push(project);
-            }
-            Ok(None) => {
-                skipped_count += 1;
-                println!("Skipping row #{} due to filtering...", record_count);
+fn validate_one_dataset(path: &str) {
+    match validate_dataset(path) {
+        Ok(result) => {
+            for column in &result.missing_columns {
+                println!("  Missing column: {}", column);
             }
-            Err(e) => {
-                println!(
-                    "Skipping row #{} due to parsing error: {}",
-                    record_count, e
-                );
-                skipped_count += 1;
+            println!(
+                "  Sampled {} rows: {} parsed cleanly, {} filtered, {} parse errors",
+                result.rows_sampled, result.rows_parsed, result.rows_filtered, result.rows_failed
+            );
+            if result.passed() {
+                println!("PASS: Dataset structure looks valid.");
+            } else {
+                println!("FAIL: Dataset does not match the expected schema.");
             }
         }
+        Err(e) => println!("ERROR: Failed to validate data: {}", e),
     }
-    println!("Skipped {} rows due to filtering or parsing errors...", skipped_count);
+}
 
-    Ok((record_count, projects))
+/// Checks that per-project data is in memory, printing why not otherwise.
+fn projects_available(config: &Config, data_loaded: bool) -> bool {
+    if !data_loaded {
+        println!("WARNING: Please load the dataset first [Option 1].");
+        return false;
+    }
+    if config.large_file {
+        println!("WARNING: This option needs per-project data, which large-file mode does not keep.");
+        return false;
+    }
+    true
 }
 
-/// Orchestrates the calculation of all reports, writes them to files, and returns the data.
-fn generate_reports(projects: &[Project]) -> Result<(Vec<InfrastructureTrends>, Vec<FinancialEfficiencies>, Vec<PerformanceMetrics>), Box<dyn Error>> {
-    let report1 = calculate_infrastructure_trends(projects);
-    let report2 = calculate_financial_efficiencies(projects);
-    let report3 = calculate_performance_metrics(projects);
-    let summary = calculate_summary_json(projects, &report2);
+fn handle_round_number_check(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+    if config.round_divisor <= 0.0 {
+        println!("ERROR: The round-number divisor must be positive.");
+        return true;
+    }
 
-    write_csv(&report1, "report1_regional_summary.csv")?;
-    
-    // Write only the Top 15 for report 2
-    let report2_top15: Vec<_> = report2.iter().take(15).collect();
-    write_csv(&report2_top15, "report2_contractor_ranking.csv")?;
-    
-    write_csv(&report3, "report3_annual_trends.csv")?;
-    write_json(&summary, "summary.json")?;
+    let flags = find_round_number_projects(projects, config.round_divisor);
+    let count_field = |field: &str| flags.iter().filter(|f| f.round_fields.contains(field)).count();
+    println!(
+        "Found {} of {} projects with amounts divisible by {}:",
+        flags.len(),
+        projects.len(),
+        fmt_peso(config.round_divisor)
+    );
+    println!("  Approved budget round: {}", count_field("approved_budget"));
+    println!("  Contract cost round:   {}", count_field("contract_cost"));
 
-    Ok((report1, report2, report3))
+    match write_csv(&flags, "round_number_flags.csv") {
+        Ok(()) => println!("SUCCESS: Flags exported to round_number_flags.csv"),
+        Err(e) => println!("ERROR: Failed to write round_number_flags.csv: {}", e),
+    }
+    true
 }
 
-/// Report 1: Calculates Infrastructure Trends
-fn calculate_infrastructure_trends(projects: &[Project]) -> Vec<InfrastructureTrends> {
-    let mut region_map: HashMap<(String, String), Vec<&Project>> = HashMap::new();
-    for p in projects {
-        region_map
-            .entry((p.region.clone(), p.main_island.clone()))
-            .or_default()
-            .push(p);
-    }
-
-    let mut report1 = Vec::new();
-    for ((region, main_island), group) in region_map {
-        let total_budget: f64 = group.iter().map(|p| p.approved_budget).sum();
-        let median_savings = calculate_median_savings(&group);
-
-        let delays: Vec<i64> = group.iter().filter_map(|p| p.completion_delay_days).collect();
-        let (avg_delay, high_delay_pct) = if !delays.is_empty() {
-            let avg = delays.iter().sum::<i64>() as f64 / delays.len() as f64;
-            let high_count = delays.iter().filter(|&&d| d > 30).count();
-            let pct = (high_count as f64 / delays.len() as f64) * 100.0;
-            (avg, pct)
-        } else {
-            (0.0, 0.0)
-        };
-        
-        let raw_score = if avg_delay.abs() > 0.001 {
-            (median_savings / avg_delay) * 100.0
-        } else {
-            0.0
-        };
-        let efficiency_score = raw_score.max(0.0).min(100.0); // per REQ-0006
+fn handle_ongoing_projects(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
 
-        report1.push(InfrastructureTrends {
-            region,
-            main_island,
-            total_budget,
-            median_savings,
-            avg_delay,
-            high_delay_pct,
-            efficiency_score,
-        });
-    }
-
-    report1.sort_by(|a, b| {
-        b.efficiency_score
-            .partial_cmp(&a.efficiency_score)
-            .unwrap_or(Ordering::Equal)
-    });
+    let today = chrono::Local::now().date_naive();
+    let report = calculate_ongoing_projects(projects, today, config.ongoing_threshold_days);
+    display_ongoing_projects(&report, config, today);
 
-    report1
+    match write_csv(&report, "ongoing_projects.csv") {
+        Ok(()) => println!("SUCCESS: Ongoing projects exported to ongoing_projects.csv"),
+        Err(e) => println!("ERROR: Failed to write ongoing_projects.csv: {}", e),
+    }
+    true
 }
 
-fn calculate_financial_efficiencies(projects: &[Project]) -> Vec<FinancialEfficiencies> {
-    let mut contractor_map: HashMap<String, Vec<&Project>> = HashMap::new();
-    for p in projects {
-        contractor_map
-            .entry(p.contractor.clone())
-            .or_default()
-            .push(p);
+fn handle_contractor_dupes(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+    if !(0.0..=1.0).contains(&config.dupe_threshold) {
+        println!("ERROR: The duplicate threshold must be between 0 and 1.");
+        return true;
+    }
+
+    let dupes = find_contractor_duplicates(projects, config.dupe_threshold);
+    println!(
+        "Found {} contractor name pairs with similarity >= {:.2}:",
+        dupes.len(),
+        config.dupe_threshold
+    );
+    for d in dupes.iter().take(10) {
+        println!("  {:.2}  {}  <->  {}", d.similarity, d.contractor_a, d.contractor_b);
+    }
+    if dupes.len() > 10 {
+        println!("  ... and {} more", dupes.len() - 10);
     }
 
-    let mut report2 = Vec::new();
-    for (contractor, group) in contractor_map {
-        let num_projects = group.len() as i32;
+    match write_csv(&dupes, "contractor_dupes.csv") {
+        Ok(()) => println!("SUCCESS: Pairs exported to contractor_dupes.csv for review (names were not merged)"),
+        Err(e) => println!("ERROR: Failed to write contractor_dupes.csv: {}", e),
+    }
+    true
+}
 
-        if num_projects < 5 { // per REQ-0007
-            continue;
-        }
+fn handle_at_budget_check(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+    if !(0.0..=1.0).contains(&config.at_budget_fraction) {
+        println!("ERROR: The at-budget fraction must be between 0 and 1.");
+        return true;
+    }
 
-        let total_cost: f64 = group.iter().map(|p| p.contract_cost).sum();
-        let total_savings: f64 = group.iter().map(|p| p.cost_savings).sum();
-        let avg_delay = calculate_avg_delay(&group);
+    let flagged = find_at_budget_contractors(projects, config.min_projects, config.at_budget_fraction);
+    println!(
+        "Found {} contractors (>={} projects) with more than {:.0}% of projects bid exactly at budget:",
+        flagged.len(),
+        config.min_projects,
+        config.at_budget_fraction * 100.0
+    );
+    for c in flagged.iter().take(10) {
+        println!(
+            "  {:>6.1}%  {:>3} of {:<3}  {}",
+            c.at_budget_pct,
+            c.at_budget_projects,
+            c.num_projects,
+            truncate(&c.contractor, 60)
+        );
+    }
+    if flagged.len() > 10 {
+        println!("  ... and {} more", flagged.len() - 10);
+    }
 
-        let total_cost_safe = if total_cost == 0.0 { 1.0 } else { total_cost };
-        let delay_factor = 1.0 - (avg_delay / 90.0);
-        let savings_factor = total_savings / total_cost_safe;
-        let raw_index = delay_factor * savings_factor * 100.0;
-        let reliability_index = raw_index.min(100.0); // per REQ-0007
+    match write_csv(&flagged, "at_budget_contractors.csv") {
+        Ok(()) => println!("SUCCESS: Contractors and their at-budget project ids exported to at_budget_contractors.csv"),
+        Err(e) => println!("ERROR: Failed to write at_budget_contractors.csv: {}", e),
+    }
+    true
+}
 
-        let risk_flag = if reliability_index < 50.0 {
-            "High Risk".to_string()
-        } else {
-            "Low Risk".to_string()
-        }; // per REQ-0007
+fn handle_repeat_contracts(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+    if !(0.0..=1.0).contains(&config.repeat_tolerance) {
+        println!("ERROR: The repeat-contract tolerance must be between 0 and 1.");
+        return true;
+    }
 
-        report2.push(FinancialEfficiencies {
-            rank: 0,
-            contractor,
-            total_cost,
-            num_projects,
-            avg_delay,
-            total_savings,
-            reliability_index,
-            risk_flag,
-        });
+    let clusters = find_repeat_contracts(projects, config.repeat_tolerance);
+    println!(
+        "Found {} clusters of same-contractor, same-region, same-type projects with budgets within {:.1}%:",
+        clusters.len(),
+        config.repeat_tolerance * 100.0
+    );
+    for c in clusters.iter().take(10) {
+        println!(
+            "  {:>3} projects  {:>18} .. {:<18}  {} / {} / {}",
+            c.num_projects,
+            fmt_peso(c.min_budget),
+            fmt_peso(c.max_budget),
+            truncate(&c.contractor, 40),
+            c.region,
+            truncate(&c.type_of_work, 40)
+        );
+    }
+    if clusters.len() > 10 {
+        println!("  ... and {} more", clusters.len() - 10);
     }
 
-    // Rank by total ContractCost (descending) per REQ-0007
-    report2.sort_by(|a, b| b.total_cost.partial_cmp(&a.total_cost).unwrap_or(Ordering::Equal));
-    
-    for (i, row) in report2.iter_mut().enumerate() {
-        row.rank = (i + 1) as i32;
+    match write_csv(&clusters, "repeat_contracts.csv") {
+        Ok(()) => println!("SUCCESS: Clusters and their project ids exported to repeat_contracts.csv"),
+        Err(e) => println!("ERROR: Failed to write repeat_contracts.csv: {}", e),
     }
+    true
+}
+
+/// `--self-test N`: builds every report from N synthetic projects with the default settings,
+/// checks the figures against their closed-form values and times each stage. Nothing is
+/// written; a failed check makes the run exit with an error.
+fn run_self_test(n: usize) -> Result<(), Box<dyn Error>> {
+    let config = Config { quiet: true, ..Config::default() };
+    println!("Self-test over {} synthetic projects (default settings)", n);
+
+    let started = std::time::Instant::now();
+    let projects = synthetic_projects(n);
+    let generated = started.elapsed();
+    let started = std::time::Instant::now();
+    let reports = build_reports(&projects, "", &config);
+    let computed = started.elapsed();
+    let started = std::time::Instant::now();
+    write_text_report(&mut io::sink(), &reports, &config, false)?;
+    let rendered = started.elapsed();
+
+    println!("  {:<24} {:>12.2?}", "Generate projects:", generated);
+    println!(
+        "  {:<24} {:>12.2?}   ({:.0} projects/s)",
+        "Compute reports:",
+        computed,
+        n as f64 / computed.as_secs_f64()
+    );
+    println!("  {:<24} {:>12.2?}", "Render text report:", rendered);
 
-    report2
+    let checks = self_test_checks(n, &reports, &config);
+    for check in &checks {
+        println!(
+            "  [{}] {:<24} expected {:>20.2}, got {:>20.2}",
+            if check.passed() { "PASS" } else { "FAIL" },
+            check.name,
+            check.expected,
+            check.actual
+        );
+    }
+    let failed = checks.iter().filter(|c| !c.passed()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} self-test checks failed", failed, checks.len()).into());
+    }
+    println!("SUCCESS: All {} self-test checks passed.", checks.len());
+    Ok(())
 }
 
-fn calculate_performance_metrics(projects: &[Project]) -> Vec<PerformanceMetrics> {
-    let mut year_type_map: HashMap<(i32, String), Vec<&Project>> = HashMap::new();
-    for p in projects {
-        year_type_map
-            .entry((p.funding_year, p.type_of_work.clone()))
-            .or_default()
-            .push(p);
-    }
-
-    let mut savings_map: HashMap<(i32, String), f64> = HashMap::new();
-    let mut report3 = Vec::new();
-
-    for ((year, work_type), group) in &year_type_map {
-        let total_projects = group.len() as i32;
-        let avg_savings =
-            group.iter().map(|p| p.cost_savings).sum::<f64>() / total_projects as f64;
-        let overrun_count = group
-            .iter()
-            .filter(|p| p.contract_cost > p.approved_budget)
-            .count();
-        let overrun_rate = (overrun_count as f64 / total_projects as f64) * 100.0;
-
-        savings_map.insert((*year, work_type.clone()), avg_savings);
-
-        report3.push(PerformanceMetrics {
-            funding_year: *year,
-            type_of_work: work_type.clone(),
-            total_projects,
-            avg_savings,
-            overrun_rate,
-            yoy_change: 0.0,
-        });
-    }
-
-    // Calculate YoY
-    for row in report3.iter_mut() {
-        if row.funding_year == 2021 {
-            row.yoy_change = 0.0; // Baseline year
-        } else {
-            let prev_year_savings =
-                savings_map.get(&(row.funding_year - 1, row.type_of_work.clone()));
-            
-            if let Some(prev_savings) = prev_year_savings {
-                if *prev_savings != 0.0 {
-                    row.yoy_change =
-                        ((row.avg_savings - prev_savings) / prev_savings.abs()) * 100.0;
-                } else {
-                    row.yoy_change = if row.avg_savings > 0.0 { 100.0 } else { 0.0 };
-                }
-            } else {
-                row.yoy_change = 0.0; // No data for previous year
-            }
+/// Runs the load with the current filters but only reports row counts by outcome;
+/// nothing is kept, so reports still need a real load.
+fn handle_dry_run(config: &Config) -> Result<bool, Box<dyn Error>> {
+    let paths = prompt_dataset_paths(config)?;
+    let quiet_config = Config { quiet: true, ..config.clone() };
+    let work_type_terms: Vec<String> = config.work_types.iter().map(|t| t.to_lowercase()).collect();
+    let mut work_type_matches = 0;
+
+    let _step = InterruptibleStep::begin();
+    let summary = match load_data_with(&paths, &quiet_config, |project| {
+        if FUNDING_YEARS.contains(&project.funding_year) && matches_work_type(&project, &work_type_terms) {
+            work_type_matches += 1;
         }
+    }) {
+        Ok(summary) => summary,
+        Err(e) => {
+            println!("ERROR: Failed to load data: {}", e);
+            return Ok(true);
+        }
+    };
+
+    println!("\nDry run: {} rows read", summary.record_count);
+    if summary.interrupted {
+        println!("(Interrupted; counts cover only the rows read)");
+    }
+    if let Some(limit) = config.row_limit {
+        println!("(Limit of {} rows applied)", limit);
+    }
+    println!("  {:<36} {:>8}", "Kept:", summary.kept_count);
+    println!("  {:<36} {:>8}", "Dropped, blank field:", summary.blank_field_rows);
+    if config.keep_partial_rows {
+        println!("  {:<36} {:>8}", "Kept with blank non-critical field:", summary.partial_rows_kept);
+    }
+    println!("  {:<36} {:>8}", "Dropped, outside 2021-2023:", summary.out_of_range_rows);
+    if config.context_years > 0 {
+        println!("  {:<36} {:>8}", "Kept as YoY context only:", summary.context_rows);
     }
+    if config.start_window.is_active() {
+        println!("  {:<36} {:>8}", "Dropped, start date outside window:", summary.outside_start_window_rows);
+    }
+    if summary.implausible_year_rows > 0 {
+        println!(
+            "  {:<36} {:>8}",
+            "  of which not a plausible year:", summary.implausible_year_rows
+        );
+    }
+    println!("  {:<36} {:>8}", "Dropped, parse error:", summary.parse_error_rows);
+    println!("  {:<36} {:>8}", "Dropped, duplicate ContractId:", summary.duplicates_skipped);
+    println!("  {:<36} {:>8}", "Dropped, wrong field count:", summary.malformed_rows);
+    println!("  {:<36} {:>8}", "Kept with future completion date:", summary.future_end_dates);
+    println!("  {:<36} {:>8}", "Kept, delay > 0 days:", summary.delays.late);
+    println!("  {:<36} {:>8}", "Kept, delay exactly 0 days:", summary.delays.on_time);
+    println!("  {:<36} {:>8}", "Kept, delay < 0 days:", summary.delays.early);
+    println!("  {:<36} {:>8}", "Kept, delay missing:", summary.delays.missing);
+    if config.correct_islands {
+        println!("  {:<36} {:>8}", "Kept with corrected MainIsland:", summary.islands_corrected);
+    }
+    if config.fix_swapped_dates {
+        println!("  {:<36} {:>8}", "Kept with swapped dates:", summary.dates_swapped);
+    }
+    if !config.work_types.is_empty() {
+        println!(
+            "  {:<36} {:>8}",
+            format!("Matching type of work ({}):", config.work_types.join(", ")),
+            work_type_matches
+        );
+    }
+    println!("No data was stored; use [1] to load before generating reports.");
+    Ok(true)
+}
 
-    // Sort per REQ-0008
-    report3.sort_by(|a, b| {
-        a.funding_year.cmp(&b.funding_year).then_with(|| {
-            b.avg_savings
-                .partial_cmp(&a.avg_savings)
-                .unwrap_or(Ordering::Equal)
+/// Columns available in `scatter_budget_cost.csv`, one row per kept project.
+const SCATTER_COLUMNS: [&str; 6] = ["project_id", "approved_budget", "contract_cost", "cost_savings", "funding_year", "region"];
+
+fn handle_scatter_export(config: &Config, projects: &[Project], data_loaded: bool) -> bool {
+    if !projects_available(config, data_loaded) {
+        return true;
+    }
+
+    println!("\nScatter export columns:");
+    for (i, column) in SCATTER_COLUMNS.iter().enumerate() {
+        println!("  [{}] {}", i + 1, column);
+    }
+    let input = match prompt_line("Columns to include (numbers or names, comma-separated; blank for all): ") {
+        Ok(input) => input,
+        Err(e) => {
+            println!("ERROR: Failed to read selection: {}", e);
+            return true;
+        }
+    };
+    let columns = resolve_scatter_columns(&input);
+    if columns.is_empty() {
+        println!("WARNING: None of the selected columns exist; nothing exported.");
+        return true;
+    }
+
+    match write_scatter_csv(projects, &columns, "scatter_budget_cost.csv") {
+        Ok(()) => println!(
+            "SUCCESS: {} rows ({} columns) exported to scatter_budget_cost.csv",
+            projects.len(),
+            columns.len()
+        ),
+        Err(e) => println!("ERROR: Failed to write scatter_budget_cost.csv: {}", e),
+    }
+    true
+}
+
+/// Maps the column selection to column names, keeping the standard order. Blank selects all.
+fn resolve_scatter_columns(input: &str) -> Vec<&'static str> {
+    let terms = split_list(input);
+    if terms.is_empty() {
+        return SCATTER_COLUMNS.to_vec();
+    }
+    SCATTER_COLUMNS
+        .iter()
+        .enumerate()
+        .filter(|(i, column)| {
+            terms
+                .iter()
+                .any(|t| t.parse::<usize>() == Ok(i + 1) || t.eq_ignore_ascii_case(column))
         })
-    });
+        .map(|(_, column)| *column)
+        .collect()
+}
+
+fn write_scatter_csv(projects: &[Project], columns: &[&str], filename: &str) -> Result<(), Box<dyn Error>> {
+    let money = |v: f64| round_to(v, decimals(MONEY_DECIMALS)).to_string();
+    write_atomically(filename, |out| {
+        let mut writer = WriterBuilder::new().from_writer(out);
+        writer.write_record(columns)?;
+        for p in projects {
+            let row = columns.iter().map(|column| match *column {
+                "approved_budget" => money(p.approved_budget),
+                "contract_cost" => money(p.contract_cost),
+                "cost_savings" => money(p.cost_savings),
+                "funding_year" => p.funding_year.to_string(),
+                "project_id" => p.project_id.clone(),
+                _ => p.region.clone(),
+            });
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    })
+}
 
-    report3
+fn handle_exit() -> bool {
+    println!("Exiting application.");
+    false
 }
 
-fn calculate_summary_json(projects: &[Project], report2: &[FinancialEfficiencies]) -> SummaryJson {
-    let delays: Vec<i64> = projects.iter().filter_map(|p| p.completion_delay_days).collect();
-    let global_avg_delay = if !delays.is_empty() {
-        delays.iter().sum::<i64>() as f64 / delays.len() as f64
-    } else {
-        0.0
-    };
+fn handle_invalid_choice() -> bool {
+    println!("Invalid choice, please try again.");
+    true
+}
 
-    // provinces (per REQ-0009)
-    let total_provinces = projects
+fn display_ongoing_projects(report: &[OngoingProject], config: &Config, today: NaiveDate) {
+    let overdue_count = report.iter().filter(|r| r.overdue).count();
+    println!("\n{:-<120}", "");
+    println!("Ongoing Projects (Started, No Completion Date)");
+    println!(
+        "(Elapsed days as of {}; {} of {} exceed {} days)",
+        today,
+        overdue_count,
+        report.len(),
+        config.ongoing_threshold_days
+    );
+    println!("{:-<120}", "");
+    println!(
+        "{:<40} | {:<20} | {:>6} | {:>18} | {:<10} | {:>8} | {:<7}",
+        "Contractor", "Region", "Year", "Approved Budget", "Start", "Days", "Overdue"
+    );
+    println!("{:-<120}", "");
+
+    for r in report {
+        let contractor_name = truncate(&r.contractor, 38);
+        let region = truncate(&r.region, 18);
+
+        println!(
+            "{:<40} | {:<20} | {:>6} | {:>18} | {:<10} | {:>8} | {:<7}",
+            contractor_name,
+            region,
+            r.funding_year,
+            fmt_peso(r.approved_budget),
+            r.start_date,
+            r.days_elapsed,
+            if r.overdue { "YES" } else { "" }
+        );
+    }
+    println!("{:-<120}", "");
+}
+
+fn distinct_work_types(projects: &[Project]) -> Vec<String> {
+    let mut work_types: Vec<String> = projects
         .iter()
-        .map(|p| &p.region)
+        .map(|p| p.type_of_work.clone())
         .collect::<HashSet<_>>()
-        .len();
+        .into_iter()
+        .collect();
+    work_types.sort();
+    work_types
+}
 
-    SummaryJson {
-        total_projects_analyzed: projects.len(),
-        total_budget_analyzed: projects.iter().map(|p| p.approved_budget).sum(),
-        global_avg_delay,
-        total_contractors: report2.len(),
-        total_provinces,
-    }
+/// Turns the user's selection into filter terms; numbers pick from the listed work types,
+/// anything else is kept as a search term.
+fn resolve_work_type_selection(input: &str, work_types: &[String]) -> Vec<String> {
+    split_list(input)
+        .into_iter()
+        .map(|term| match term.parse::<usize>() {
+            Ok(n) if (1..=work_types.len()).contains(&n) => work_types[n - 1].clone(),
+            _ => term,
+        })
+        .collect()
 }
 
-fn calculate_median_savings(group: &[&Project]) -> f64 {
-    let mut savings: Vec<f64> = group.iter().map(|p| p.cost_savings).collect();
-    if savings.is_empty() {
-        return 0.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_menu_choice_accepts_numbers_in_range() {
+        assert_eq!(parse_menu_choice("1"), Ok(1));
+        assert_eq!(parse_menu_choice(" 14 \n"), Ok(MENU_OPTION_COUNT));
     }
 
-    savings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
-    let mid = savings.len() / 2;
+    #[test]
+    fn parse_menu_choice_rejects_numbers_out_of_range() {
+        for input in ["0", "15", "-3", "99999999999"] {
+            let error = parse_menu_choice(input).unwrap_err();
+            assert!(error.contains("is not a menu option; choose 1 to 14"), "{}: {}", input, error);
+        }
+    }
 
-    if savings.len() % 2 == 0 {
-        (savings[mid - 1] + savings[mid]) / 2.0
-    } else {
-        savings[mid]
+    #[test]
+    fn parse_menu_choice_matches_keywords_ignoring_case_and_whitespace() {
+        assert_eq!(parse_menu_choice("  LoAd\t"), Ok(1));
+        assert_eq!(parse_menu_choice("Dry-Run\n"), Ok(6));
+        assert_eq!(parse_menu_choice("QUIT"), Ok(14));
     }
-}
 
-fn calculate_avg_delay(group: &[&Project]) -> f64 {
-    let delays: Vec<i64> = group.iter().filter_map(|p| p.completion_delay_days).collect();
-    if delays.is_empty() {
-        0.0
-    } else {
-        delays.iter().sum::<i64>() as f64 / delays.len() as f64
+    #[test]
+    fn parse_menu_choice_rejects_blank_input() {
+        for input in ["", "   ", "\n"] {
+            assert!(parse_menu_choice(input).unwrap_err().starts_with("No choice entered."));
+        }
     }
-}
 
-// I/O Helpers
-fn write_csv<T: Serialize>(data: &[T], filename: &str) -> Result<(), Box<dyn Error>> {
-    let mut writer = WriterBuilder::new().from_path(filename)?;
-    for row in data {
-        writer.serialize(row)?;
+    #[test]
+    fn parse_menu_choice_rejects_unknown_words() {
+        let error = parse_menu_choice("Loader").unwrap_err();
+        assert!(error.starts_with("Unrecognized choice \"Loader\". Use 1-14 or one of: load, report,"), "{}", error);
     }
-    writer.flush()?;
-    Ok(())
 }
-
-fn write_json<T: Serialize>(data: &T, filename: &str) -> Result<(), Box<dyn Error>> {
-    let file = File::create(filename)?;
-    serde_json::to_writer_pretty(file, data)?;
-    Ok(())
-}
\ No newline at end of file
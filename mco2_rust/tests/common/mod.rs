@@ -0,0 +1,95 @@
+//! Builders shared by the integration tests: in-memory projects, and dataset files in the
+//! source layout for the tests that go through `load_data`.
+#![allow(dead_code)] // each test file uses its own subset
+
+use chrono::NaiveDate;
+use mco2_rust::Project;
+use std::path::PathBuf;
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+}
+
+/// A 2022 Luzon project in the National Capital Region that started on 2022-01-03 and took
+/// `delay` days; None leaves both the completion date and the delay missing.
+pub fn project(contractor: &str, approved_budget: f64, contract_cost: f64, delay: Option<i64>) -> Project {
+    let start = date(2022, 1, 3);
+    Project {
+        project_id: format!("{}-{}", contractor, approved_budget),
+        region: "National Capital Region".to_string(),
+        province: "Metro Manila".to_string(),
+        main_island: "Luzon".to_string(),
+        contractor: contractor.to_string(),
+        funding_year: 2022,
+        type_of_work: "Construction of Flood Mitigation Structure".to_string(),
+        approved_budget,
+        contract_cost,
+        cost_savings: approved_budget - contract_cost,
+        start_date: Some(start),
+        end_date: delay.map(|days| start + chrono::Duration::days(days)),
+        completion_delay_days: delay,
+    }
+}
+
+/// `project` moved to another region and main island.
+pub fn project_in(region: &str, main_island: &str, approved_budget: f64, contract_cost: f64, delay: Option<i64>) -> Project {
+    Project {
+        region: region.to_string(),
+        main_island: main_island.to_string(),
+        ..project("Contractor A", approved_budget, contract_cost, delay)
+    }
+}
+
+pub const HEADER: [&str; 22] = [
+    "MainIsland", "Region", "Province", "LegislativeDistrict", "Municipality", "DistrictEngineeringOffice",
+    "ProjectId", "ProjectName", "TypeOfWork", "FundingYear", "ContractId", "ApprovedBudgetForContract",
+    "ContractCost", "ActualCompletionDate", "Contractor", "ContractorCount", "StartDate", "ProjectLatitude",
+    "ProjectLongitude", "ProvincialCapital", "ProvincialCapitalLatitude", "ProvincialCapitalLongitude",
+];
+
+/// A complete source row (2022, budget 1,000,000, cost 900,000, 2022-01-03 to 2022-03-04)
+/// with the given (column index, value) replacements.
+pub fn row(changes: &[(usize, &str)]) -> Vec<String> {
+    let mut fields: Vec<String> = [
+        "Luzon", "National Capital Region", "Metro Manila", "District 1", "Manila", "Manila DEO",
+        "P001", "Flood Wall", "Construction of Flood Mitigation Structure", "2022", "22AA0001", "1000000",
+        "900000", "2022-03-04", "Contractor A", "1", "2022-01-03", "14.6", "121.0", "Manila", "14.6", "121.0",
+    ]
+    .map(str::to_string)
+    .to_vec();
+    for (index, value) in changes {
+        fields[*index] = value.to_string();
+    }
+    fields
+}
+
+/// Writes `rows` under the standard header to a file named after `name` in the temp
+/// directory and returns its path. Names must be unique across tests, which run in parallel.
+pub fn write_dataset(name: &str, rows: &[Vec<String>]) -> String {
+    write_dataset_with_header(name, &HEADER, rows)
+}
+
+/// `write_dataset` with a header of the caller's choosing.
+pub fn write_dataset_with_header(name: &str, header: &[&str], rows: &[Vec<String>]) -> String {
+    let path = temp_path(&format!("{}.csv", name));
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_path(&path).expect("create dataset");
+    writer.write_record(header).expect("write header");
+    for row in rows {
+        writer.write_record(row).expect("write row");
+    }
+    writer.flush().expect("flush dataset");
+    path.to_string_lossy().into_owned()
+}
+
+/// A path in the temp directory unique to this test process.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mco2_rust_{}_{}", std::process::id(), name))
+}
+
+/// A config with console output off, as a batch run would use.
+pub fn quiet_config() -> mco2_rust::Config {
+    mco2_rust::Config {
+        quiet: true,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,252 @@
+//! Dataset files loaded through `load_data`, and what the filters keep.
+mod common;
+
+use common::{HEADER, date, quiet_config, row, temp_path, write_dataset, write_dataset_with_header};
+use mco2_rust::{Config, StartDateWindow, build_report1, generate_summary, load_data};
+use std::process::{Command, Output, Stdio};
+
+#[test]
+fn header_only_file_is_refused() {
+    let path = write_dataset("header_only", &[]);
+
+    let (summary, projects) = load_data(std::slice::from_ref(&path), &quiet_config()).unwrap();
+    assert_eq!(summary.record_count, 0);
+    assert!(projects.is_empty());
+
+    // A batch run stops before writing any report
+    let run_dir = temp_path("header_only_run");
+    std::fs::create_dir_all(&run_dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_mco2_rust"))
+        .args(["--generate", &path])
+        .current_dir(&run_dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No projects matched the filters"));
+    assert_eq!(std::fs::read_dir(&run_dir).unwrap().count(), 0);
+    std::fs::remove_dir_all(run_dir).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn island_correction_merges_a_mislabeled_region_into_one_group() {
+    let path = write_dataset("island_fix", &[row(&[]), row(&[(0, "Visayas"), (10, "22AA0002")])]);
+    let load = |correct_islands| {
+        let config = Config { correct_islands, ..quiet_config() };
+        let (summary, projects) = load_data(std::slice::from_ref(&path), &config).unwrap();
+        (summary.islands_corrected, build_report1(&projects, &config))
+    };
+
+    let (corrected, report1) = load(false);
+    assert_eq!(corrected, 0);
+    assert_eq!(report1.len(), 2);
+
+    let (corrected, report1) = load(true);
+    assert_eq!(corrected, 1);
+    assert_eq!(report1.len(), 1);
+    assert_eq!((report1[0].main_island.as_str(), report1[0].total_budget), ("Luzon", 2_000_000.0));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn far_future_completion_dates_are_counted_and_optionally_nulled() {
+    // Within the default 30-day margin, so not flagged
+    let soon = (chrono::Local::now().date_naive() + chrono::Duration::days(10)).to_string();
+    let path = write_dataset(
+        "future_end",
+        &[row(&[(13, "2099-01-01")]), row(&[(13, soon.as_str()), (10, "22AA0002")])],
+    );
+    let load = |null_future_delays| {
+        let config = Config { null_future_delays, ..quiet_config() };
+        load_data(std::slice::from_ref(&path), &config).unwrap()
+    };
+
+    let (summary, projects) = load(false);
+    assert_eq!(summary.future_end_dates, 1);
+    assert!(projects[0].completion_delay_days.is_some_and(|d| d > 27_000));
+
+    let (summary, projects) = load(true);
+    assert_eq!(summary.future_end_dates, 1);
+    assert_eq!(projects[0].completion_delay_days, None);
+    assert!(projects[1].completion_delay_days.is_some());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn row_with_a_shifted_column_is_skipped() {
+    // An unquoted comma in the contractor name splits it into two fields
+    let mut shifted = row(&[(14, "SMITH"), (10, "22AA0002")]);
+    shifted.insert(15, " JR. BUILDERS".to_string());
+    let path = write_dataset("mismatched_columns", &[row(&[(14, "SMITH, JR. BUILDERS")]), shifted]);
+
+    let (summary, projects) = load_data(std::slice::from_ref(&path), &quiet_config()).unwrap();
+    assert_eq!((summary.record_count, summary.malformed_rows, summary.skipped_count), (2, 1, 1));
+    assert_eq!(projects.len(), 1);
+    // The quoted comma stays part of the name
+    assert_eq!(projects[0].contractor, "SMITH, JR. BUILDERS");
+
+    let strict = Config { strict: true, ..quiet_config() };
+    let error = load_data(std::slice::from_ref(&path), &strict).unwrap_err().to_string();
+    assert!(error.contains("row #2: expected 22 fields, found 23"), "{}", error);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn keep_partial_rows_drops_only_blank_critical_fields() {
+    let path = write_dataset(
+        "keep_partial",
+        &[
+            row(&[]),
+            row(&[(10, "22AA0002"), (4, "")]),
+            row(&[(10, "22AA0003"), (14, " ")]),
+            row(&[(10, "22AA0004"), (12, "")]),
+        ],
+    );
+    let load = |keep_partial_rows| {
+        let config = Config { keep_partial_rows, ..quiet_config() };
+        load_data(std::slice::from_ref(&path), &config).unwrap()
+    };
+
+    // By default any blank field drops the row
+    let (summary, projects) = load(false);
+    assert_eq!((summary.blank_field_rows, summary.partial_rows_kept), (3, 0));
+    assert_eq!(projects.len(), 1);
+
+    // Kept: a blank unused column, and a blank contractor shown as "Unspecified"; dropped: a blank cost
+    let (summary, projects) = load(true);
+    assert_eq!((summary.blank_field_rows, summary.partial_rows_kept), (1, 2));
+    let contractors: Vec<&str> = projects.iter().map(|p| p.contractor.as_str()).collect();
+    assert_eq!(contractors, ["Contractor A", "Contractor A", "Unspecified"]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn start_window_bounds_are_inclusive() {
+    let starts = ["2022-01-31", "2022-02-01", "2022-06-30", "2022-07-01", "n/a"];
+    let rows: Vec<Vec<String>> = starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| row(&[(16, start), (10, &format!("22AA000{}", i))]))
+        .collect();
+    let path = write_dataset("start_window", &rows);
+    let load = |start_window| {
+        let config = Config { start_window, ..quiet_config() };
+        load_data(std::slice::from_ref(&path), &config).unwrap()
+    };
+
+    let (summary, projects) = load(StartDateWindow::default());
+    assert_eq!((projects.len(), summary.outside_start_window_rows), (5, 0));
+
+    let window = StartDateWindow { from: Some(date(2022, 2, 1)), to: Some(date(2022, 6, 30)) };
+    let (summary, projects) = load(window);
+    // An unparseable start date never passes an active window
+    assert_eq!(summary.outside_start_window_rows, 3);
+    let kept: Vec<_> = projects.iter().map(|p| p.start_date).collect();
+    assert_eq!(kept, [Some(date(2022, 2, 1)), Some(date(2022, 6, 30))]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn aliased_reordered_header_without_province_keeps_its_rows() {
+    // Standard columns renamed to accepted aliases, Province left out, and the order reversed
+    let renamed = |name: &'static str| match name {
+        "FundingYear" => "Fiscal Year",
+        "ApprovedBudgetForContract" => "ABC",
+        "Contractor" => "Contractor Name",
+        _ => name,
+    };
+    let reshape = |fields: Vec<String>| -> Vec<String> {
+        let mut fields: Vec<String> = fields.into_iter().enumerate().filter(|(i, _)| *i != 2).map(|(_, f)| f).collect();
+        fields.reverse();
+        fields
+    };
+    let mut header: Vec<&str> = HEADER.iter().copied().filter(|h| *h != "Province").map(renamed).collect();
+    header.reverse();
+    let rows = vec![
+        reshape(row(&[])),
+        reshape(row(&[(0, "Visayas"), (1, "Region VII"), (10, "22AA0002"), (14, "Contractor B")])),
+    ];
+    let path = write_dataset_with_header("no_province", &header, &rows);
+
+    let (summary, projects) = load_data(std::slice::from_ref(&path), &quiet_config()).unwrap();
+    assert_eq!((summary.kept_count, summary.blank_field_rows), (2, 0));
+    assert_eq!(projects[1].contractor, "Contractor B");
+    assert_eq!((projects[1].funding_year, projects[1].approved_budget), (2022, 1_000_000.0));
+    assert!(projects.iter().all(|p| p.province.is_empty()));
+    // Without a Province column the region count stands in for the province count
+    assert_eq!(generate_summary(&projects, &quiet_config()).total_provinces, 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn fix_swapped_dates_swaps_completion_before_start() {
+    let path = write_dataset("swapped_dates", &[row(&[(13, "2022-01-03"), (16, "2022-03-04")])]);
+
+    let (summary, projects) = load_data(std::slice::from_ref(&path), &quiet_config()).unwrap();
+    assert_eq!(summary.dates_swapped, 0);
+    assert_eq!(projects[0].completion_delay_days, Some(-60));
+
+    let config = Config { fix_swapped_dates: true, ..quiet_config() };
+    let (summary, projects) = load_data(std::slice::from_ref(&path), &config).unwrap();
+    assert_eq!(summary.dates_swapped, 1);
+    assert_eq!(projects[0].start_date, Some(date(2022, 1, 3)));
+    assert_eq!(projects[0].end_date, Some(date(2022, 3, 4)));
+    assert_eq!(projects[0].completion_delay_days, Some(60));
+    std::fs::remove_file(path).unwrap();
+}
+
+/// Runs the binary in a fresh directory under the temp dir, with `stdin` as its standard input.
+fn run_in(dir_name: &str, args: &[&str], stdin: Stdio) -> (Output, std::path::PathBuf) {
+    let dir = temp_path(dir_name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_mco2_rust"))
+        .args(args)
+        .current_dir(&dir)
+        .stdin(stdin)
+        .output()
+        .unwrap();
+    (output, dir)
+}
+
+#[test]
+fn dataset_piped_to_stdin_matches_the_same_file() {
+    let rows: Vec<Vec<String>> = (0..12)
+        .map(|i| {
+            let (budget, contract_id) = (format!("{}", 1_000_000 + 25_000 * i), format!("22AA{:04}", i));
+            let contractor = format!("Contractor {}", i % 2);
+            row(&[(11, budget.as_str()), (10, contract_id.as_str()), (14, contractor.as_str())])
+        })
+        .collect();
+    let path = write_dataset("stdin_source", &rows);
+
+    let (piped, piped_dir) = run_in("stdin_piped", &["--generate", "-"], Stdio::from(std::fs::File::open(&path).unwrap()));
+    let (direct, direct_dir) = run_in("stdin_direct", &["--generate", &path], Stdio::null());
+    assert!(piped.status.success(), "{}", String::from_utf8_lossy(&piped.stderr));
+    assert!(direct.status.success(), "{}", String::from_utf8_lossy(&direct.stderr));
+    for name in ["summary.json", "report1_regional_summary.csv"] {
+        let piped_bytes = std::fs::read(piped_dir.join(name)).unwrap();
+        assert_eq!(piped_bytes, std::fs::read(direct_dir.join(name)).unwrap(), "{} differs", name);
+    }
+    // The fingerprint covers the piped bytes, so both runs record the file's hash
+    let summary = std::fs::read_to_string(piped_dir.join("summary.json")).unwrap();
+    assert!(summary.contains("\"dataset_sha256\": \""), "{}", summary);
+
+    std::fs::remove_dir_all(piped_dir).unwrap();
+    std::fs::remove_dir_all(direct_dir).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn stdin_dataset_needs_generate_and_no_other_files() {
+    let cases: [(&str, &[&str], &str); 2] = [
+        ("stdin_menu", &["-"], "needs --generate"),
+        ("stdin_mixed", &["--generate", "-", "other.csv"], "cannot be combined with other dataset files"),
+    ];
+    for (dir_name, args, expected) in cases {
+        let (output, dir) = run_in(dir_name, args, Stdio::null());
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(expected), "{:?}: {}", args, stderr);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
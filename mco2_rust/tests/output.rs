@@ -0,0 +1,74 @@
+//! Written report files read back through the public API.
+mod common;
+
+use common::{project, project_in, quiet_config, temp_path};
+use mco2_rust::{FinancialEfficiencies, InfrastructureTrends, Project, build_reports, write_csv};
+use serde::de::DeserializeOwned;
+
+/// Within the rounding the CSV applies (two decimals at most).
+fn assert_close(written: f64, read: f64) {
+    assert!((written - read).abs() <= 0.005 + f64::EPSILON, "wrote {}, read back {}", written, read);
+}
+
+fn assert_close_opt(written: Option<f64>, read: Option<f64>) {
+    match (written, read) {
+        (Some(w), Some(r)) => assert_close(w, r),
+        (w, r) => assert_eq!(w, r),
+    }
+}
+
+fn round_trip<T: serde::Serialize + DeserializeOwned>(rows: &[T], name: &str) -> Vec<T> {
+    let path = temp_path(name).to_string_lossy().into_owned();
+    write_csv(rows, &path).unwrap();
+    let read: Vec<T> = csv::Reader::from_path(&path)
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    std::fs::remove_file(path).unwrap();
+    read
+}
+
+fn sample_projects() -> Vec<Project> {
+    let mut projects: Vec<Project> = (0..7)
+        .map(|i| project("Contractor A", 1_000_000.0 + 333.33 * i as f64, 950_000.0, Some(7 * i + 1)))
+        .collect();
+    projects.extend((0..5).map(|i| Project {
+        contractor: "Contractor B".to_string(),
+        ..project_in("Region VII", "Visayas", 2_000_000.0, 1_990_000.0 + 7_777.77 * i as f64, None)
+    }));
+    projects
+}
+
+#[test]
+fn report_csvs_read_back_to_the_written_values() {
+    let reports = build_reports(&sample_projects(), "abc", &quiet_config());
+    assert_eq!(reports.report1.len(), 2);
+    assert_eq!(reports.report2.len(), 2);
+
+    let report1: Vec<InfrastructureTrends> = round_trip(&reports.report1, "round_trip_report1.csv");
+    assert_eq!(report1.len(), reports.report1.len());
+    for (w, r) in reports.report1.iter().zip(&report1) {
+        assert_eq!((&w.region, &w.main_island, w.low_sample), (&r.region, &r.main_island, r.low_sample));
+        assert_close(w.total_budget, r.total_budget);
+        assert_close(w.median_savings, r.median_savings);
+        assert_close_opt(w.avg_delay, r.avg_delay);
+        assert_close(w.high_delay_pct, r.high_delay_pct);
+        assert_close(w.overrun_rate, r.overrun_rate);
+        assert_close(w.efficiency_score, r.efficiency_score);
+    }
+
+    let report2: Vec<FinancialEfficiencies> = round_trip(&reports.report2, "round_trip_report2.csv");
+    assert_eq!(report2.len(), reports.report2.len());
+    for (w, r) in reports.report2.iter().zip(&report2) {
+        assert_eq!((w.rank, &w.contractor, w.num_projects), (r.rank, &r.contractor, r.num_projects));
+        assert_eq!((&w.risk_flag, &w.savings_anomaly_flag, &w.trend), (&r.risk_flag, &r.savings_anomaly_flag, &r.trend));
+        assert_close(w.total_cost, r.total_cost);
+        assert_close(w.market_share_pct, r.market_share_pct);
+        assert_close(w.avg_project_cost, r.avg_project_cost);
+        assert_close_opt(w.avg_delay, r.avg_delay);
+        assert_close(w.total_savings, r.total_savings);
+        assert_close(w.savings_rate_pct, r.savings_rate_pct);
+        assert_close(w.reliability_index, r.reliability_index);
+    }
+}
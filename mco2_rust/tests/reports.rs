@@ -0,0 +1,246 @@
+//! Report figures computed from hand-built projects through the public API.
+mod common;
+
+use common::{project, project_in, quiet_config};
+use mco2_rust::{
+    Config, MissingDelayPolicy, OverrunMargin, Project, RankMode, RankStyle, ReportKind, build_report1, build_report2, build_reports,
+    collect_delays, generate_summary, percentile_value,
+};
+
+/// Six projects for Contractor A (enough for Report 2) and one for Contractor B, across two regions.
+fn sample_projects() -> Vec<Project> {
+    let mut projects: Vec<Project> = (0..6)
+        .map(|i| project("Contractor A", 1_000_000.0, 900_000.0 + 10_000.0 * i as f64, Some(10 * i)))
+        .collect();
+    projects.push(Project {
+        contractor: "Contractor B".to_string(),
+        ..project_in("Region VII", "Visayas", 2_000_000.0, 2_100_000.0, Some(100))
+    });
+    projects
+}
+
+#[test]
+fn build_reports_groups_regions_and_ranks_contractors() {
+    let projects = sample_projects();
+    let reports = build_reports(&projects, "abc", &quiet_config());
+
+    assert_eq!(reports.report1.len(), 2);
+    let ncr = reports.report1.iter().find(|r| r.region == "National Capital Region").unwrap();
+    assert_eq!(ncr.total_budget, 6_000_000.0);
+    assert_eq!(ncr.median_savings, 75_000.0);
+    assert_eq!(ncr.avg_delay, Some(25.0));
+    assert_eq!(ncr.overrun_rate, 0.0);
+    let visayas = reports.report1.iter().find(|r| r.region == "Region VII").unwrap();
+    assert_eq!(visayas.overrun_rate, 100.0);
+
+    // Contractor B has one project, below the default minimum of five
+    assert_eq!(reports.report2.len(), 1);
+    assert_eq!(reports.report2[0].contractor, "Contractor A");
+    assert_eq!(reports.report2[0].rank, 1);
+    assert_eq!(reports.report2[0].num_projects, 6);
+    assert_eq!(reports.report2[0].total_cost, 5_550_000.0);
+    assert_eq!(reports.non_finite_replaced, 0);
+}
+
+#[test]
+fn generate_summary_totals_the_projects() {
+    let projects = sample_projects();
+    let summary = generate_summary(&projects, &quiet_config());
+
+    assert_eq!(summary.total_projects_analyzed, 7);
+    assert_eq!(summary.total_budget_analyzed, 8_000_000.0);
+    assert_eq!(summary.global_avg_delay, Some(250.0 / 7.0));
+    assert_eq!(summary.overrun_project_count, 1);
+    assert_eq!(summary.total_overrun_amount, 100_000.0);
+    assert_eq!(summary.budget_by_year.get(&2022), Some(&8_000_000.0));
+    assert_eq!(summary.island_breakdown.len(), 2);
+}
+
+#[test]
+fn build_reports_summary_matches_generate_summary() {
+    let projects = sample_projects();
+    let config = quiet_config();
+    let from_reports = build_reports(&projects, "abc", &config).summary;
+    let standalone = generate_summary(&projects, &config);
+
+    assert_eq!(from_reports.dataset_sha256, "abc");
+    assert_eq!(from_reports.total_projects_analyzed, standalone.total_projects_analyzed);
+    assert_eq!(from_reports.national_efficiency_score, standalone.national_efficiency_score);
+    assert_eq!(from_reports.total_contractors, standalone.total_contractors);
+}
+
+/// Five projects each at `cost`, so every contractor clears the Report 2 minimum.
+fn contractor_projects(contractor: &str, cost: f64) -> Vec<Project> {
+    (0..5).map(|_| project(contractor, cost, cost, Some(10))).collect()
+}
+
+#[test]
+fn tied_costs_are_ranked_by_the_rank_style() {
+    let projects: Vec<Project> = [("Top", 2_000_000.0), ("Tied 1", 1_000_000.0), ("Tied 2", 1_000_000.0), ("Last", 200_000.0)]
+        .iter()
+        .flat_map(|(contractor, cost)| contractor_projects(contractor, *cost))
+        .collect();
+    let ranks = |rank_style| {
+        let config = Config { rank_style, ..quiet_config() };
+        let report2 = build_report2(&projects, &config);
+        let rank_of = |name: &str| report2.iter().find(|r| r.contractor == name).unwrap().rank;
+        let mut tied = [rank_of("Tied 1"), rank_of("Tied 2")];
+        tied.sort();
+        (rank_of("Top"), tied, rank_of("Last"))
+    };
+
+    assert_eq!(ranks(RankStyle::Ordinal), (1, [2, 3], 4));
+    assert_eq!(ranks(RankStyle::Competition), (1, [2, 2], 4));
+    assert_eq!(ranks(RankStyle::Dense), (1, [2, 2], 3));
+}
+
+#[test]
+fn missing_delays_are_excluded_or_counted_as_zero() {
+    let delays = [Some(10), Some(20), Some(30), None, None];
+    let projects: Vec<Project> = delays.iter().map(|&d| project("Contractor A", 1_000_000.0, 900_000.0, d)).collect();
+    let averages = |missing_delay| {
+        let config = Config { missing_delay, ..quiet_config() };
+        let reports = build_reports(&projects, "", &config);
+        (reports.report1[0].avg_delay, reports.report2[0].avg_delay, reports.summary.global_avg_delay)
+    };
+
+    assert_eq!(averages(MissingDelayPolicy::Exclude), (Some(20.0), Some(20.0), Some(20.0)));
+    assert_eq!(averages(MissingDelayPolicy::TreatAsZero), (Some(12.0), Some(12.0), Some(12.0)));
+}
+
+#[test]
+fn weighted_median_follows_the_big_budget_project() {
+    // Savings 10,000 and 20,000 on 1M budgets, 500,000 on an 8M budget holding most of the weight
+    let projects = vec![
+        project("Contractor A", 1_000_000.0, 990_000.0, Some(10)),
+        project("Contractor A", 1_000_000.0, 980_000.0, Some(10)),
+        project("Contractor A", 8_000_000.0, 7_500_000.0, Some(10)),
+    ];
+
+    let plain = build_report1(&projects, &quiet_config());
+    assert_eq!(plain[0].median_savings, 20_000.0);
+    assert_eq!(plain[0].weighted_median_savings, None);
+
+    let weighted = build_report1(&projects, &Config { weighted_median: true, ..quiet_config() });
+    assert_eq!(weighted[0].median_savings, 20_000.0);
+    assert_eq!(weighted[0].weighted_median_savings, Some(500_000.0));
+}
+
+#[test]
+fn rank_mode_changes_the_order() {
+    // Big: most cost, no savings. Saver: most savings, but late. Steady: on time with some savings.
+    let group = |contractor: &str, budget: f64, cost: f64, delay| vec![project(contractor, budget, cost, Some(delay)); 5];
+    let projects: Vec<Project> = [
+        group("Big", 2_000_000.0, 2_000_000.0, 10),
+        group("Saver", 3_000_000.0, 1_500_000.0, 80),
+        group("Steady", 1_000_000.0, 800_000.0, 0),
+    ]
+    .concat();
+    let order = |rank_by| {
+        let mut report2 = build_report2(&projects, &Config { rank_by, ..quiet_config() });
+        report2.sort_by_key(|r| r.rank);
+        report2.into_iter().map(|r| r.contractor).collect::<Vec<_>>()
+    };
+
+    assert_eq!(order(RankMode::Cost), ["Big", "Saver", "Steady"]);
+    assert_eq!(order(RankMode::Savings), ["Saver", "Steady", "Big"]);
+    assert_eq!(order(RankMode::Reliability), ["Steady", "Saver", "Big"]);
+}
+
+#[test]
+fn nan_inputs_never_reach_the_output() {
+    for name in ["approved_budget", "contract_cost", "cost_savings"] {
+        let mut projects: Vec<Project> = (0..5).map(|i| project("Contractor A", 1_000_000.0, 900_000.0, Some(i))).collect();
+        let poisoned = &mut projects[0];
+        *match name {
+            "approved_budget" => &mut poisoned.approved_budget,
+            "contract_cost" => &mut poisoned.contract_cost,
+            _ => &mut poisoned.cost_savings,
+        } = f64::NAN;
+        let reports = build_reports(&projects, "", &quiet_config());
+
+        assert!(reports.non_finite_replaced > 0, "NaN {} was not counted", name);
+        for table in reports.tables().unwrap() {
+            for cell in table.rows.iter().flatten() {
+                assert!(!cell.contains("NaN") && !cell.contains("inf"), "NaN {} reached {}: {}", name, table.title, cell);
+            }
+        }
+        let summary = &reports.summary;
+        assert!(summary.total_budget_analyzed.is_finite() && summary.national_efficiency_score.is_finite(), "NaN {}", name);
+        assert!(summary.contractor_hhi.is_finite() && summary.budget_gini.is_finite(), "NaN {}", name);
+    }
+}
+
+#[test]
+fn overrun_margin_is_exclusive_at_the_boundary() {
+    // Budget 1,000,000 with excesses of 0, 0.01, 50,000 and 50,000.01
+    let projects: Vec<Project> = [1_000_000.0, 1_000_000.01, 1_050_000.0, 1_050_000.01]
+        .iter()
+        .map(|&cost| project("Contractor A", 1_000_000.0, cost, Some(10)))
+        .collect();
+    let overruns = |margin: &str| {
+        let overrun_margin: OverrunMargin = margin.parse().unwrap();
+        generate_summary(&projects, &Config { overrun_margin, ..quiet_config() }).overrun_project_count
+    };
+
+    assert_eq!(overruns("0"), 3);
+    assert_eq!(overruns("50000"), 1);
+    assert_eq!(overruns("5%"), 1);
+    assert_eq!(overruns("49999.99"), 2);
+    assert_eq!(overruns("4.999999%"), 2);
+}
+
+#[test]
+fn capping_delays_lowers_the_averages() {
+    let projects: Vec<Project> = [10, 10, 10, 10, 400]
+        .iter()
+        .map(|&d| project("Contractor A", 1_000_000.0, 900_000.0, Some(d)))
+        .collect();
+    let uncapped = build_reports(&projects, "", &quiet_config());
+    assert_eq!(uncapped.report1[0].avg_delay, Some(88.0));
+    assert_eq!(uncapped.report2[0].avg_delay, Some(88.0));
+
+    // Resolved the way main does for --cap-delays 80: the 80th percentile is 10 days
+    let mut delays = collect_delays(&projects, MissingDelayPolicy::Exclude);
+    let config = Config {
+        delay_cap_percentile: Some(80.0),
+        delay_cap: percentile_value(&mut delays, 80.0),
+        ..quiet_config()
+    };
+    let capped = build_reports(&projects, "", &config);
+    assert_eq!(capped.summary.delay_cap_days, Some(10));
+    assert_eq!(capped.report1[0].avg_delay, Some(10.0));
+    assert_eq!(capped.report2[0].avg_delay, Some(10.0));
+    assert!(capped.report2[0].reliability_index > uncapped.report2[0].reliability_index);
+    // The summary's global average stays uncapped
+    assert_eq!(capped.summary.global_avg_delay, Some(88.0));
+}
+
+#[test]
+fn cost_floor_excludes_low_cost_contractors() {
+    // Totals of 5,000,000 and 500,000
+    let projects = [contractor_projects("Large", 1_000_000.0), contractor_projects("Small", 100_000.0)].concat();
+    let ranked = |min_total_cost| {
+        let reports = build_reports(&projects, "", &Config { min_total_cost, ..quiet_config() });
+        let contractors: Vec<String> = reports.report2.iter().map(|r| r.contractor.clone()).collect();
+        (contractors, reports.report2_below_cost_floor, reports.summary.total_contractors)
+    };
+
+    assert_eq!(ranked(None), (vec!["Large".to_string(), "Small".to_string()], 0, 2));
+    // The floor itself is not "below" it
+    assert_eq!(ranked(Some(500_000.0)).1, 0);
+    assert_eq!(ranked(Some(500_000.01)), (vec!["Large".to_string()], 1, 1));
+}
+
+#[test]
+fn summary_only_run_skips_report_1_but_keeps_the_national_score() {
+    let mut projects = sample_projects();
+    projects.extend((0..3).map(|i| project_in("Region XI", "Mindanao", 3_000_000.0, 2_500_000.0, Some(40 + i))));
+    let full = build_reports(&projects, "", &quiet_config());
+    let summary_only = build_reports(&projects, "", &Config { reports: vec![ReportKind::Summary], ..quiet_config() });
+
+    assert!(summary_only.report1.is_empty());
+    assert!(full.summary.national_efficiency_score > 0.0);
+    let (a, b) = (full.summary.national_efficiency_score, summary_only.summary.national_efficiency_score);
+    assert!((a - b).abs() < 1e-9, "with Report 1 {}, without {}", a, b);
+}
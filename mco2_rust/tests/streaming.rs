@@ -0,0 +1,114 @@
+//! Large-file mode (`stream_reports`) against the batch path over the same dataset.
+mod common;
+
+use common::{date, quiet_config, row, write_dataset};
+use mco2_rust::{Config, build_reports, load_data, stream_reports};
+
+fn assert_close(batch: f64, streamed: f64, what: &str) {
+    assert!((batch - streamed).abs() <= 1e-6 * batch.abs().max(1.0), "{}: batch {}, streamed {}", what, batch, streamed);
+}
+
+fn assert_close_opt(batch: Option<f64>, streamed: Option<f64>, what: &str) {
+    match (batch, streamed) {
+        (Some(b), Some(s)) => assert_close(b, s, what),
+        (b, s) => assert_eq!(b, s, "{}", what),
+    }
+}
+
+/// 90 rows over three regions, four contractors and 2021-2023, with overruns, early
+/// completions and a missing completion date on every eleventh row.
+fn varied_dataset(name: &str) -> String {
+    let regions = [("Luzon", "National Capital Region"), ("Visayas", "Region VII"), ("Mindanao", "Region XI")];
+    let rows: Vec<Vec<String>> = (0..90)
+        .map(|i| {
+            let (island, region) = regions[i % 3];
+            let budget = 1_000_000.0 + 12_345.67 * i as f64;
+            let cost = budget * (0.85 + 0.05 * (i % 7) as f64);
+            let start = date(2021, 1, 1) + chrono::Duration::days(i as i64 * 5);
+            let end = if i % 11 == 0 {
+                "n/a".to_string()
+            } else {
+                (start + chrono::Duration::days((i as i64 * 13) % 200 - 20)).to_string()
+            };
+            let fields = [
+                (0, island.to_string()),
+                (1, region.to_string()),
+                (9, (2021 + (i / 3) % 3).to_string()),
+                (10, format!("C{:04}", i)),
+                (11, format!("{:.2}", budget)),
+                (12, format!("{:.2}", cost)),
+                (13, end),
+                (14, format!("Contractor {}", i % 4)),
+                (16, start.to_string()),
+            ];
+            let changes: Vec<(usize, &str)> = fields.iter().map(|(index, value)| (*index, value.as_str())).collect();
+            row(&changes)
+        })
+        .collect();
+    write_dataset(name, &rows)
+}
+
+#[test]
+fn streamed_reports_match_batch_reports() {
+    let path = varied_dataset("streaming_vs_batch");
+    let paths = std::slice::from_ref(&path);
+    let config = Config { weighted_median: true, raw_scores: true, ..quiet_config() };
+    let (_, projects) = load_data(paths, &config).unwrap();
+    let batch = build_reports(&projects, "", &config);
+    let (_, streamed) = stream_reports(paths, &Config { large_file: true, ..config.clone() }).unwrap();
+
+    assert_eq!(batch.report1.len(), 3);
+    assert_eq!(batch.report1.len(), streamed.report1.len());
+    for b in &batch.report1 {
+        let s = streamed.report1.iter().find(|s| s.region == b.region).unwrap();
+        assert_close(b.total_budget, s.total_budget, "total_budget");
+        assert_close(b.median_savings, s.median_savings, "median_savings");
+        assert_close_opt(b.weighted_median_savings, s.weighted_median_savings, "weighted_median_savings");
+        assert_close_opt(b.avg_delay, s.avg_delay, "avg_delay");
+        assert_close(b.high_delay_pct, s.high_delay_pct, "high_delay_pct");
+        assert_close(b.overrun_rate, s.overrun_rate, "overrun_rate");
+        assert_close(b.efficiency_score, s.efficiency_score, "efficiency_score");
+        assert_close_opt(b.efficiency_raw, s.efficiency_raw, "efficiency_raw");
+    }
+
+    assert_eq!(batch.report2.len(), 4);
+    assert_eq!(batch.report2.len(), streamed.report2.len());
+    for (b, s) in batch.report2.iter().zip(&streamed.report2) {
+        assert_eq!((b.rank, &b.contractor, b.num_projects), (s.rank, &s.contractor, s.num_projects));
+        assert_eq!((&b.risk_flag, &b.savings_anomaly_flag, &b.trend), (&s.risk_flag, &s.savings_anomaly_flag, &s.trend));
+        assert_close(b.total_cost, s.total_cost, "total_cost");
+        assert_close(b.market_share_pct, s.market_share_pct, "market_share_pct");
+        assert_close_opt(b.avg_delay, s.avg_delay, "avg_delay");
+        assert_close(b.total_savings, s.total_savings, "total_savings");
+        assert_close(b.savings_rate_pct, s.savings_rate_pct, "savings_rate_pct");
+        assert_close(b.savings_per_delay_day, s.savings_per_delay_day, "savings_per_delay_day");
+        assert_close(b.reliability_index, s.reliability_index, "reliability_index");
+        assert_close_opt(b.reliability_raw, s.reliability_raw, "reliability_raw");
+    }
+
+    assert_eq!(batch.report3.len(), streamed.report3.len());
+    for b in &batch.report3 {
+        let s = streamed
+            .report3
+            .iter()
+            .find(|s| (s.funding_year, &s.type_of_work) == (b.funding_year, &b.type_of_work))
+            .unwrap();
+        assert_eq!(b.total_projects, s.total_projects);
+        assert_close(b.total_savings, s.total_savings, "total_savings");
+        assert_close(b.avg_savings, s.avg_savings, "avg_savings");
+        assert_close(b.overrun_rate, s.overrun_rate, "overrun_rate");
+        assert_close(b.avg_utilization_pct, s.avg_utilization_pct, "avg_utilization_pct");
+        assert_close(b.yoy_change, s.yoy_change, "yoy_change");
+    }
+
+    let (b, s) = (&batch.summary, &streamed.summary);
+    assert_eq!(b.total_projects_analyzed, s.total_projects_analyzed);
+    assert_eq!((b.total_contractors, b.total_provinces, b.overrun_project_count), (s.total_contractors, s.total_provinces, s.overrun_project_count));
+    assert_close(b.total_budget_analyzed, s.total_budget_analyzed, "total_budget_analyzed");
+    assert_close_opt(b.global_avg_delay, s.global_avg_delay, "global_avg_delay");
+    assert_close(b.national_efficiency_score, s.national_efficiency_score, "national_efficiency_score");
+    assert_close(b.total_overrun_amount, s.total_overrun_amount, "total_overrun_amount");
+    assert_close(b.contractor_hhi, s.contractor_hhi, "contractor_hhi");
+    assert_close(b.budget_gini, s.budget_gini, "budget_gini");
+    std::fs::remove_file(path).unwrap();
+}